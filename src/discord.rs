@@ -0,0 +1,462 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use eframe::egui::Color32;
+use serde::Deserialize;
+
+use crate::identity;
+use crate::notation::Notations;
+use crate::spreadsheet::{DEFAULT_COLOR, Name, Row};
+
+/// Guesses imported from Discord, along with the messages that were left out.
+#[derive(Debug, Default)]
+pub struct Import {
+    pub rows: Vec<Row>,
+    pub flagged: Vec<Flagged>,
+}
+
+/// A message that was not used as a guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flagged {
+    /// Line in the log the message starts on, or its position in an export.
+    pub line: u32,
+    pub name: String,
+    pub message: String,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    NotAGuess,
+    /// Made up of squares, but not as many as the board has, like a `yes` in reply to someone.
+    WrongLength,
+    /// The author sent a later guess, which is the one that counts.
+    Superseded,
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Self::NotAGuess => "Doesn't look like a guess",
+            Self::WrongLength => "Not a square for every square of the board",
+            Self::Superseded => "Replaced by a later guess",
+        };
+
+        write!(f, "{reason}")
+    }
+}
+
+struct Message {
+    line: u32,
+    name: String,
+    color: Color32,
+    timestamp: Option<String>,
+    content: String,
+}
+
+/// Reads a channel log copied out of Discord and saved as a text file.
+pub fn read_log<P: AsRef<Path>>(
+    path: P,
+    notations: &Notations,
+    squares: usize,
+) -> std::io::Result<Import> {
+    Ok(parse_log(
+        &std::fs::read_to_string(path)?,
+        notations,
+        squares,
+    ))
+}
+
+/// Parses a channel log copied out of Discord into rows, keeping only the latest guess of each author. Only messages
+/// with as many squares as the board, `squares`, count as a guess.
+///
+/// A copied log is made up of blocks that start with a `Name — Today at 3:04 PM` header, followed by the lines of the
+/// message. Further messages by the same author are separated by a `[3:05 PM]` line instead of a new header.
+#[must_use]
+pub fn parse_log(log: &str, notations: &Notations, squares: usize) -> Import {
+    let mut messages: Vec<Message> = Vec::new();
+    let mut author: Option<String> = None;
+
+    for (idx, line) in log.lines().enumerate() {
+        let num = idx as u32 + 1;
+
+        if let Some(name) = header(line) {
+            author = Some(name.to_string());
+            messages.push(Message {
+                line: num + 1,
+                name: name.to_string(),
+                color: DEFAULT_COLOR,
+                timestamp: None,
+                content: String::new(),
+            });
+            continue;
+        }
+
+        let Some(name) = &author else {
+            continue;
+        };
+
+        if is_timestamp(line) {
+            messages.push(Message {
+                line: num + 1,
+                name: name.clone(),
+                color: DEFAULT_COLOR,
+                timestamp: None,
+                content: String::new(),
+            });
+            continue;
+        }
+
+        if let Some(message) = messages.last_mut() {
+            if !message.content.is_empty() {
+                message.content.push('\n');
+            }
+
+            message.content.push_str(line.trim());
+        }
+    }
+
+    import(&messages, notations, squares)
+}
+
+/// Reads a channel exported to JSON by DiscordChatExporter.
+pub fn read_export<P: AsRef<Path>>(
+    path: P,
+    notations: &Notations,
+    squares: usize,
+) -> std::io::Result<Import> {
+    Ok(parse_export(
+        &std::fs::read_to_string(path)?,
+        notations,
+        squares,
+    )?)
+}
+
+/// Parses a channel exported to JSON by DiscordChatExporter into rows, keeping only the latest guess of each author.
+/// Only messages with as many squares as the board, `squares`, count as a guess.
+///
+/// Names are coloured by the author's top role, and each row keeps when its message was sent. Rows are numbered by
+/// the position of their message in the export. Messages from bots are left out.
+pub fn parse_export(
+    json: &str,
+    notations: &Notations,
+    squares: usize,
+) -> serde_json::Result<Import> {
+    let export: Export = serde_json::from_str(json)?;
+
+    let messages: Vec<Message> = export
+        .messages
+        .into_iter()
+        .enumerate()
+        .filter(|(_, message)| !message.author.is_bot)
+        .map(|(idx, message)| Message {
+            line: idx as u32 + 1,
+            color: message.author.color(),
+            name: message.author.nickname.unwrap_or(message.author.name),
+            timestamp: Some(message.timestamp),
+            content: message.content.trim().to_string(),
+        })
+        .collect();
+
+    Ok(import(&messages, notations, squares))
+}
+
+#[derive(Deserialize)]
+struct Export {
+    messages: Vec<ExportMessage>,
+}
+
+#[derive(Deserialize)]
+struct ExportMessage {
+    timestamp: String,
+    content: String,
+    author: Author,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Author {
+    name: String,
+    nickname: Option<String>,
+    color: Option<String>,
+    #[serde(default)]
+    is_bot: bool,
+    #[serde(default)]
+    roles: Vec<Role>,
+}
+
+#[derive(Deserialize)]
+struct Role {
+    color: Option<String>,
+    #[serde(default)]
+    position: i64,
+}
+
+impl Author {
+    /// The colour of the highest role that has one, which is the colour Discord shows the name in.
+    fn color(&self) -> Color32 {
+        let role = || {
+            self.roles
+                .iter()
+                .filter(|role| role.color.is_some())
+                .max_by_key(|role| role.position)
+                .and_then(|role| role.color.as_deref())
+        };
+
+        self.color
+            .as_deref()
+            .or_else(role)
+            .and_then(|hex| Color32::from_hex(hex).ok())
+            .unwrap_or(DEFAULT_COLOR)
+    }
+}
+
+fn import(messages: &[Message], notations: &Notations, squares: usize) -> Import {
+    let mut import = Import::default();
+
+    for (idx, message) in messages.iter().enumerate() {
+        if message.content.trim().is_empty() {
+            continue;
+        }
+
+        let Some(length) = guess_length(&message.content, notations) else {
+            import.flagged.push(flag(message, Reason::NotAGuess));
+            continue;
+        };
+
+        if length != squares {
+            import.flagged.push(flag(message, Reason::WrongLength));
+            continue;
+        }
+
+        let superseded = messages[idx + 1..].iter().any(|later| {
+            identity::same(&later.name, &message.name)
+                && guess_length(&later.content, notations) == Some(squares)
+        });
+
+        if superseded {
+            import.flagged.push(flag(message, Reason::Superseded));
+            continue;
+        }
+
+        import.rows.push(Row {
+            num: message.line,
+            name: Name::new(message.name.clone(), message.color),
+            guess: message.content.replace('\n', " "),
+            starting_score: 0,
+            submitted: message.timestamp.clone(),
+        });
+    }
+
+    import
+}
+
+fn flag(message: &Message, reason: Reason) -> Flagged {
+    Flagged {
+        line: message.line,
+        name: message.name.clone(),
+        message: message.content.clone(),
+        reason,
+    }
+}
+
+/// The author name of a `Name — Today at 3:04 PM` line.
+fn header(line: &str) -> Option<&str> {
+    let (name, time) = line.split_once(" — ")?;
+    let name = name.trim();
+
+    (!name.is_empty() && time.chars().any(|ch| ch.is_ascii_digit())).then_some(name)
+}
+
+/// A `[3:05 PM]` line, which separates messages by the same author.
+fn is_timestamp(line: &str) -> bool {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .is_some_and(|time| {
+            time.contains(':')
+                && time
+                    .chars()
+                    .all(|ch| ch.is_ascii_digit() || matches!(ch, ':' | ' ' | 'A' | 'P' | 'M'))
+        })
+}
+
+/// Number of squares in a message made up of only squares, in any notation, and the separators people put between
+/// them.
+fn guess_length(message: &str, notations: &Notations) -> Option<usize> {
+    let parsed = notations.parse(message);
+
+    (parsed.dropped.is_empty() && !parsed.guess.is_empty()).then(|| parsed.guess.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+Rolo — Today at 3:04 PM
+YYNN YNYN NNYY
+Edits — Today at 3:05 PM
+good luck everyone
+[3:06 PM]
+YNYN YNYN YNYN
+Rolo — Today at 3:10 PM
+NNNN
+NNNN NNNN";
+
+    #[test]
+    fn should_keep_latest_guess_per_author() {
+        let import = parse_log(LOG, &Notations::default(), 12);
+
+        let rows: Vec<(&str, &str, u32)> = import
+            .rows
+            .iter()
+            .map(|row| (row.name().text(), row.guess(), row.num()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("Edits", "YNYN YNYN YNYN", 6),
+                ("Rolo", "NNNN NNNN NNNN", 8),
+            ],
+            rows
+        );
+    }
+
+    const EXPORT: &str = r##"{
+  "guild": { "id": "1", "name": "Tower of God" },
+  "channel": { "id": "2", "name": "bingo" },
+  "messages": [
+    {
+      "id": "10",
+      "type": "Default",
+      "timestamp": "2024-03-01T15:04:05.123+00:00",
+      "content": "YYNN YNYN",
+      "author": { "id": "100", "name": "rolo", "nickname": "Rolo", "color": "#ED4245", "isBot": false, "roles": [] }
+    },
+    {
+      "id": "11",
+      "type": "Default",
+      "timestamp": "2024-03-01T15:05:00.000+00:00",
+      "content": "Bingo is open!",
+      "author": { "id": "200", "name": "Bingo Bot", "nickname": null, "color": null, "isBot": true, "roles": [] }
+    },
+    {
+      "id": "12",
+      "type": "Default",
+      "timestamp": "2024-03-01T16:00:00.000+00:00",
+      "content": "NNYY PPYN",
+      "author": {
+        "id": "300",
+        "name": "edits",
+        "nickname": null,
+        "color": null,
+        "isBot": false,
+        "roles": [
+          { "id": "1", "name": "Silver", "color": "#c0c0c0", "position": 2 },
+          { "id": "2", "name": "Mod", "color": null, "position": 9 },
+          { "id": "3", "name": "Red", "color": "#ed4245", "position": 1 }
+        ]
+      }
+    },
+    {
+      "id": "13",
+      "type": "Default",
+      "timestamp": "2024-03-01T17:00:00.000+00:00",
+      "content": "YYYY YYYY",
+      "author": { "id": "100", "name": "rolo", "nickname": "Rolo", "color": "#ED4245", "isBot": false, "roles": [] }
+    }
+  ],
+  "messageCount": 4
+}"##;
+
+    #[test]
+    fn should_import_export() {
+        let import = parse_export(EXPORT, &Notations::default(), 8).unwrap();
+
+        let rows: Vec<(u32, &str, Color32, &str, Option<&str>)> = import
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.num(),
+                    row.name().text(),
+                    row.name().color(),
+                    row.guess(),
+                    row.submitted(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                (
+                    3,
+                    "edits",
+                    Color32::from_rgb(0xc0, 0xc0, 0xc0),
+                    "NNYY PPYN",
+                    Some("2024-03-01T16:00:00.000+00:00")
+                ),
+                (
+                    4,
+                    "Rolo",
+                    Color32::from_rgb(0xed, 0x42, 0x45),
+                    "YYYY YYYY",
+                    Some("2024-03-01T17:00:00.000+00:00")
+                ),
+            ],
+            rows
+        );
+        assert_eq!(1, import.flagged.len());
+        assert_eq!(Reason::Superseded, import.flagged[0].reason);
+    }
+
+    #[test]
+    fn should_flag_messages() {
+        let import = parse_log(LOG, &Notations::default(), 12);
+
+        let flagged: Vec<(&str, Reason)> = import
+            .flagged
+            .iter()
+            .map(|flagged| (flagged.name.as_str(), flagged.reason))
+            .collect();
+
+        assert_eq!(
+            vec![("Rolo", Reason::Superseded), ("Edits", Reason::NotAGuess)],
+            flagged
+        );
+    }
+
+    #[test]
+    fn should_not_replace_guess_with_reply() {
+        let log = "\
+Rolo — Today at 3:04 PM
+YYNN YNYN
+Edits — Today at 3:05 PM
+is this right?
+Rolo — Today at 3:06 PM
+yes
+[3:07 PM]
+✅";
+
+        let import = parse_log(log, &Notations::default(), 8);
+
+        assert_eq!(1, import.rows.len());
+        assert_eq!("YYNN YNYN", import.rows[0].guess());
+
+        let flagged: Vec<(&str, Reason)> = import
+            .flagged
+            .iter()
+            .map(|flagged| (flagged.message.as_str(), flagged.reason))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("is this right?", Reason::NotAGuess),
+                ("yes", Reason::WrongLength),
+                ("✅", Reason::WrongLength),
+            ],
+            flagged
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
 #[derive(Debug)]
 pub enum Error {
     DoubleGuesser {
@@ -10,4 +13,211 @@ pub enum Error {
         amount: usize,
         needed: usize,
     },
+    TooManyWagers {
+        name: String,
+        row: u32,
+        amount: usize,
+        limit: usize,
+    },
+    TooManyPasses {
+        name: String,
+        row: u32,
+        amount: usize,
+        limit: usize,
+    },
+    InvalidKey(KeyError),
+    /// An error in one of the weeks of a season, by the name of its sheet.
+    InWeek {
+        week: String,
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Spreadsheet row of the guess with the problem, if it is with a guess.
+    pub fn row(&self) -> Option<u32> {
+        match self {
+            Self::DoubleGuesser { row, .. }
+            | Self::NotEnoughValidSquares { row, .. }
+            | Self::TooManyWagers { row, .. }
+            | Self::TooManyPasses { row, .. } => Some(*row),
+            Self::InvalidKey(_) => None,
+            Self::InWeek { error, .. } => error.row(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// A character that isn't a square. Positions are one based, in characters.
+    Unrecognised { position: usize, character: char },
+    /// The key doesn't have an answer, `?` or `X` for every square of the board.
+    WrongLength { amount: usize, needed: usize },
+}
+
+impl Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unrecognised {
+                position,
+                character,
+            } => write!(
+                f,
+                "`{character}` at position {position} of the key is not one of `Y`, `N`, `?` or `X`"
+            ),
+            Self::WrongLength { amount, needed } => {
+                write!(
+                    f,
+                    "the key has `{amount}` squares, the board has `{needed}`"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BoardError {
+    Io { path: PathBuf, reason: String },
+    Parse { path: PathBuf, reason: String },
+    UnsupportedFormat { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub enum TeamError {
+    Io { path: PathBuf, reason: String },
+    Parse { path: PathBuf, reason: String },
+}
+
+#[derive(Debug, Clone)]
+pub enum SpreadsheetError {
+    /// The file couldn't be read as an `xlsx` workbook.
+    Open {
+        path: PathBuf,
+        reason: String,
+    },
+    MissingSheet {
+        path: PathBuf,
+        sheet: String,
+    },
+    /// The workbook couldn't be saved back to its file.
+    Write {
+        path: PathBuf,
+        reason: String,
+    },
+    /// Rows and columns are numbered from 1.
+    Cell {
+        path: PathBuf,
+        sheet: String,
+        row: u32,
+        column: u32,
+        reason: String,
+    },
+    /// A line of a `csv` or `tsv` file, numbered from 1.
+    Line {
+        path: PathBuf,
+        line: u32,
+        reason: String,
+    },
+}
+
+impl Display for SpreadsheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Open { path, reason } => {
+                write!(f, "failed to open `{}`: {reason}", path.display())
+            }
+            Self::MissingSheet { path, sheet } => {
+                write!(f, "`{}` has no sheet named `{sheet}`", path.display())
+            }
+            Self::Write { path, reason } => {
+                write!(f, "failed to save `{}`: {reason}", path.display())
+            }
+            Self::Cell {
+                path,
+                sheet,
+                row,
+                column,
+                reason,
+            } => write!(
+                f,
+                "`{}`, sheet `{sheet}`, cell {}{row}: {reason}",
+                path.display(),
+                crate::spreadsheet::column_name(*column),
+            ),
+            Self::Line { path, line, reason } => {
+                write!(f, "`{}`, line {line}: {reason}", path.display())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NotationError {
+    Io { path: PathBuf, reason: String },
+    Parse { path: PathBuf, reason: String },
+}
+
+#[derive(Debug, Clone)]
+pub enum AliasError {
+    Io { path: PathBuf, reason: String },
+    Parse { path: PathBuf, reason: String },
+}
+
+impl Display for AliasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, reason } => {
+                write!(f, "failed to read aliases `{}`: {reason}", path.display())
+            }
+            Self::Parse { path, reason } => {
+                write!(f, "invalid aliases `{}`: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, reason } => {
+                write!(f, "failed to read notations `{}`: {reason}", path.display())
+            }
+            Self::Parse { path, reason } => {
+                write!(f, "invalid notations `{}`: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl Display for TeamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, reason } => {
+                write!(f, "failed to read teams `{}`: {reason}", path.display())
+            }
+            Self::Parse { path, reason } => {
+                write!(f, "invalid teams `{}`: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, reason } => {
+                write!(f, "failed to read board `{}`: {reason}", path.display())
+            }
+            Self::Parse { path, reason } => {
+                write!(f, "invalid board `{}`: {reason}", path.display())
+            }
+            Self::UnsupportedFormat { path } => {
+                write!(
+                    f,
+                    "board `{}` must be a `toml` or `json` file",
+                    path.display()
+                )
+            }
+        }
+    }
 }
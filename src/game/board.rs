@@ -0,0 +1,575 @@
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::BoardError;
+use crate::game::score_card::Outcome;
+
+/// The layout and point values of a bingo card.
+///
+/// Built in modes construct theirs from a `board!` constant, while custom modes are loaded from `toml` or `json`
+/// files:
+///
+/// ```toml
+/// name = "Mini"
+/// rows = [
+///     [10, 20, 30],
+///     [10, 20, 30],
+/// ]
+/// # Optional, points lost on a miss when they differ from the points won on a hit.
+/// penalties = [
+///     [0, 10, 30],
+///     [0, 10, 30],
+/// ]
+/// # Optional, points for passing on a square, negative for a cost.
+/// pass = -5
+/// # Optional, how many squares a player can pass on.
+/// max_passes = 3
+///
+/// [wager]
+/// multiplier = 2
+/// limit = 2
+///
+/// [bonus]
+/// row = 50
+/// blackout = 200
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BoardFile")]
+pub struct Board {
+    name: String,
+    /// Points won on a hit.
+    rows: Vec<Vec<i32>>,
+    /// Points lost on a miss, in the same shape as `rows`.
+    penalties: Vec<Vec<i32>>,
+    pass: i32,
+    max_passes: Option<usize>,
+    bonus: Bonuses,
+    wager: Wagers,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardFile {
+    name: String,
+    rows: Vec<Vec<i32>>,
+    penalties: Option<Vec<Vec<i32>>>,
+    #[serde(default)]
+    pass: i32,
+    max_passes: Option<usize>,
+    #[serde(default)]
+    bonus: Bonuses,
+    #[serde(default)]
+    wager: Wagers,
+}
+
+/// Extra points for completing a whole line of correct guesses, on top of the points of the squares themselves.
+///
+/// Rows are every row of the board. Columns and diagonals are taken from the grid formed by the leading rows that
+/// share the width of the first row, so a trailing row of tie-breaker squares doesn't break them up. Diagonals only
+/// exist when that grid is square.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bonuses {
+    pub row: i32,
+    pub column: i32,
+    pub diagonal: i32,
+    pub blackout: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
+    Blackout,
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Row(row) => write!(f, "Row {}", row + 1),
+            Self::Column(column) => write!(f, "Column {}", column + 1),
+            Self::Diagonal => write!(f, "Diagonal"),
+            Self::AntiDiagonal => write!(f, "Anti-Diagonal"),
+            Self::Blackout => write!(f, "Blackout"),
+        }
+    }
+}
+
+/// How much a wagered square is worth, and how many squares each player can wager on.
+///
+/// No wagers are allowed unless a board sets a `limit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Wagers {
+    pub multiplier: i32,
+    pub limit: usize,
+}
+
+impl Default for Wagers {
+    fn default() -> Self {
+        Self {
+            multiplier: 2,
+            limit: 0,
+        }
+    }
+}
+
+/// A completed line and the points it was worth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bonus {
+    pub line: Line,
+    pub points: i32,
+}
+
+impl TryFrom<BoardFile> for Board {
+    type Error = String;
+
+    fn try_from(file: BoardFile) -> Result<Self, Self::Error> {
+        if file.name.trim().is_empty() {
+            return Err(String::from("board `name` cannot be empty"));
+        }
+
+        if file.rows.is_empty() || file.rows.iter().any(Vec::is_empty) {
+            return Err(String::from(
+                "board `rows` must have at least one square in every row",
+            ));
+        }
+
+        if file.wager.multiplier <= 0 {
+            return Err(String::from("board `wager.multiplier` must be more than 0"));
+        }
+
+        let penalties = match file.penalties {
+            Some(penalties) => {
+                let same_shape = penalties.len() == file.rows.len()
+                    && penalties
+                        .iter()
+                        .zip(&file.rows)
+                        .all(|(penalties, rewards)| penalties.len() == rewards.len());
+
+                if !same_shape {
+                    return Err(String::from(
+                        "board `penalties` must have the same shape as `rows`",
+                    ));
+                }
+
+                penalties
+            }
+            None => file.rows.clone(),
+        };
+
+        Ok(Self {
+            name: file.name,
+            rows: file.rows,
+            penalties,
+            pass: file.pass,
+            max_passes: file.max_passes,
+            bonus: file.bonus,
+            wager: file.wager,
+        })
+    }
+}
+
+impl Board {
+    /// # Panics
+    ///
+    /// If there are no rows, or a row has no squares.
+    #[must_use]
+    pub fn new(name: &str, rows: &[&[i32]]) -> Self {
+        assert!(
+            !rows.is_empty() && rows.iter().all(|row| !row.is_empty()),
+            "board must have at least one square in every row"
+        );
+
+        let rows: Vec<Vec<i32>> = rows.iter().map(|row| row.to_vec()).collect();
+
+        Self {
+            name: name.to_string(),
+            penalties: rows.clone(),
+            rows,
+            pass: 0,
+            max_passes: None,
+            bonus: Bonuses::default(),
+            wager: Wagers::default(),
+        }
+    }
+
+    /// Replaces the points lost on a miss, which otherwise match the points won on a hit.
+    ///
+    /// # Panics
+    ///
+    /// If `penalties` is not the same shape as the board.
+    #[must_use]
+    pub fn with_penalties(mut self, penalties: &[&[i32]]) -> Self {
+        assert!(
+            penalties
+                .iter()
+                .map(|row| row.len())
+                .eq(self.rows.iter().map(Vec::len)),
+            "penalties must have the same shape as the board"
+        );
+
+        self.penalties = penalties.iter().map(|row| row.to_vec()).collect();
+        self
+    }
+
+    #[must_use]
+    pub fn with_pass(mut self, pass: i32) -> Self {
+        self.pass = pass;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_passes(mut self, max_passes: usize) -> Self {
+        self.max_passes = Some(max_passes);
+        self
+    }
+
+    /// # Panics
+    ///
+    /// If the multiplier is not more than 0.
+    #[must_use]
+    pub fn with_wagers(mut self, wager: Wagers) -> Self {
+        assert!(wager.multiplier > 0, "wager multiplier must be more than 0");
+
+        self.wager = wager;
+        self
+    }
+
+    #[must_use]
+    pub fn with_bonuses(mut self, bonus: Bonuses) -> Self {
+        self.bonus = bonus;
+        self
+    }
+
+    /// Loads a board from a `toml` or `json` file, picking the format from the extension.
+    pub fn load(path: &Path) -> Result<Self, BoardError> {
+        let contents = fs::read_to_string(path).map_err(|err| BoardError::Io {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })?;
+
+        let board = match path.extension().and_then(OsStr::to_str) {
+            Some("toml") => toml::from_str(&contents).map_err(|err| err.message().to_string()),
+            Some("json") => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+            _ => {
+                return Err(BoardError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                });
+            }
+        };
+
+        board.map_err(|reason| BoardError::Parse {
+            path: path.to_path_buf(),
+            reason,
+        })
+    }
+
+    /// Loads every `toml` and `json` board in a directory, sorted by file name.
+    ///
+    /// A missing directory is not an error, it just means there are no custom boards.
+    #[must_use]
+    pub fn load_dir(dir: &Path) -> (Vec<Self>, Vec<BoardError>) {
+        let mut boards = Vec::new();
+        let mut errors = Vec::new();
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (boards, errors);
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(OsStr::to_str),
+                        Some("toml" | "json")
+                    )
+            })
+            .collect();
+
+        paths.sort();
+
+        for path in paths {
+            match Self::load(&path) {
+                Ok(board) => boards.push(board),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        (boards, errors)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rows(&self) -> &[Vec<i32>] {
+        &self.rows
+    }
+
+    /// Total number of squares on the board.
+    pub fn squares(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Points won on a hit for every square, in reading order.
+    pub fn rewards(&self) -> impl Iterator<Item = i32> + '_ {
+        self.rows.iter().flatten().copied()
+    }
+
+    /// Points lost on a miss for every square, in reading order.
+    pub fn penalties(&self) -> impl Iterator<Item = i32> + '_ {
+        self.penalties.iter().flatten().copied()
+    }
+
+    /// Points for passing on a square.
+    pub fn pass(&self) -> i32 {
+        self.pass
+    }
+
+    pub fn bonuses(&self) -> Bonuses {
+        self.bonus
+    }
+
+    /// How many squares a player can pass on, if there is a limit.
+    pub fn max_passes(&self) -> Option<usize> {
+        self.max_passes
+    }
+
+    pub fn wagers(&self) -> Wagers {
+        self.wager
+    }
+
+    /// Every line on the board that is worth a bonus, with the indices of the squares that make it up.
+    pub fn lines(&self) -> Vec<(Line, Vec<usize>)> {
+        let mut lines = Vec::new();
+
+        let mut offsets = Vec::with_capacity(self.rows.len());
+        let mut offset = 0;
+        for row in &self.rows {
+            offsets.push(offset);
+            offset += row.len();
+        }
+
+        if self.bonus.row != 0 {
+            for (row, squares) in self.rows.iter().enumerate() {
+                lines.push((
+                    Line::Row(row),
+                    (offsets[row]..offsets[row] + squares.len()).collect(),
+                ));
+            }
+        }
+
+        let width = self.rows.first().map_or(0, Vec::len);
+        let height = self
+            .rows
+            .iter()
+            .take_while(|row| row.len() == width)
+            .count();
+
+        if self.bonus.column != 0 && height > 1 {
+            for column in 0..width {
+                lines.push((
+                    Line::Column(column),
+                    offsets[..height]
+                        .iter()
+                        .map(|offset| offset + column)
+                        .collect(),
+                ));
+            }
+        }
+
+        if self.bonus.diagonal != 0 && height == width && width > 1 {
+            lines.push((
+                Line::Diagonal,
+                (0..width).map(|idx| offsets[idx] + idx).collect(),
+            ));
+            lines.push((
+                Line::AntiDiagonal,
+                (0..width)
+                    .map(|idx| offsets[idx] + width - 1 - idx)
+                    .collect(),
+            ));
+        }
+
+        if self.bonus.blackout != 0 {
+            lines.push((Line::Blackout, (0..offset).collect()));
+        }
+
+        lines
+    }
+
+    /// Bonuses earned for the lines where every square was a hit.
+    ///
+    /// Voided squares don't break a line, but a line needs at least one hit to count.
+    pub fn line_bonuses(&self, outcomes: &[Outcome]) -> Vec<Bonus> {
+        self.lines()
+            .into_iter()
+            .filter(|(_, squares)| {
+                let outcomes = || squares.iter().map(|&square| outcomes.get(square));
+
+                outcomes().all(|outcome| matches!(outcome, Some(Outcome::Hit | Outcome::Void)))
+                    && outcomes().any(|outcome| outcome == Some(&Outcome::Hit))
+            })
+            .map(|(line, _)| Bonus {
+                line,
+                points: match line {
+                    Line::Row(_) => self.bonus.row,
+                    Line::Column(_) => self.bonus.column,
+                    Line::Diagonal | Line::AntiDiagonal => self.bonus.diagonal,
+                    Line::Blackout => self.bonus.blackout,
+                },
+            })
+            .collect()
+    }
+}
+
+/// Number of squares in a `board!` constant.
+#[must_use]
+pub const fn squares(board: &[&[i32]]) -> usize {
+    let mut squares = 0;
+    let mut row = 0;
+
+    while row < board.len() {
+        squares += board[row].len();
+        row += 1;
+    }
+
+    squares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_toml() {
+        let board: Board = toml::from_str(
+            r#"
+name = "Mini"
+rows = [
+    [10, 20, 30],
+    [40, 50, 60],
+]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!("Mini", board.name());
+        assert_eq!(6, board.squares());
+        assert_eq!(
+            vec![10, 20, 30, 40, 50, 60],
+            board.rewards().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_parse_json() {
+        let board: Board =
+            serde_json::from_str(r#"{ "name": "Mini", "rows": [[10, 20], [30]] }"#).unwrap();
+
+        assert_eq!(Board::new("Mini", &[&[10, 20], &[30]]), board);
+    }
+
+    #[test]
+    fn should_find_lines_on_grid() {
+        let board = Board::new("Grid", &[&[1, 1, 1], &[1, 1, 1], &[1, 1, 1], &[1, 1]])
+            .with_bonuses(Bonuses {
+                row: 1,
+                column: 1,
+                diagonal: 1,
+                blackout: 1,
+            });
+
+        let lines = board.lines();
+
+        // 4 rows, 3 columns, 2 diagonals and the blackout.
+        assert_eq!(10, lines.len());
+        assert!(lines.contains(&(Line::Column(2), vec![2, 5, 8])));
+        assert!(lines.contains(&(Line::AntiDiagonal, vec![2, 4, 6])));
+        assert!(lines.contains(&(Line::Row(3), vec![9, 10])));
+    }
+
+    #[test]
+    fn should_award_completed_lines() {
+        let board = Board::new("Grid", &[&[1, 1], &[1, 1]]).with_bonuses(Bonuses {
+            row: 10,
+            column: 20,
+            diagonal: 0,
+            blackout: 100,
+        });
+
+        let bonuses =
+            board.line_bonuses(&[Outcome::Hit, Outcome::Void, Outcome::Miss, Outcome::Hit]);
+
+        assert_eq!(
+            vec![
+                Bonus {
+                    line: Line::Row(0),
+                    points: 10
+                },
+                Bonus {
+                    line: Line::Column(1),
+                    points: 20
+                },
+            ],
+            bonuses
+        );
+    }
+
+    #[test]
+    fn should_default_penalties_to_rewards() {
+        let board: Board =
+            serde_json::from_str(r#"{ "name": "Mini", "rows": [[10, 20]] }"#).unwrap();
+
+        assert_eq!(vec![10, 20], board.penalties().collect::<Vec<_>>());
+        assert_eq!(0, board.pass());
+    }
+
+    #[test]
+    fn should_reject_mismatched_penalties() {
+        let board = serde_json::from_str::<Board>(
+            r#"{ "name": "Mini", "rows": [[10, 20]], "penalties": [[10]] }"#,
+        );
+
+        assert!(board.is_err());
+    }
+
+    #[test]
+    fn should_reject_empty_rows() {
+        let board = serde_json::from_str::<Board>(r#"{ "name": "Mini", "rows": [[10], []] }"#);
+
+        assert!(board.is_err());
+
+        let board = serde_json::from_str::<Board>(r#"{ "name": "Mini", "rows": [] }"#);
+
+        assert!(board.is_err());
+    }
+
+    #[test]
+    fn should_reject_non_positive_multiplier() {
+        for multiplier in [0, -2] {
+            let board = toml::from_str::<Board>(&format!(
+                "name = \"Mini\"\nrows = [[10]]\n\n[wager]\nmultiplier = {multiplier}\n"
+            ));
+
+            assert!(board.is_err(), "{multiplier}");
+        }
+    }
+
+    #[test]
+    fn should_reject_unknown_fields() {
+        let board =
+            serde_json::from_str::<Board>(r#"{ "name": "Mini", "rows": [[10]], "max_pass": 1 }"#);
+
+        assert!(board.is_err());
+    }
+}
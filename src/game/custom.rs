@@ -0,0 +1,166 @@
+use crate::{
+    Player,
+    error::Error,
+    game::{Game, board::Board, duplicates::Duplicates},
+    spreadsheet::Row,
+};
+
+/// A mode whose board was loaded from a file rather than compiled in.
+#[derive(Debug)]
+pub struct Custom {
+    board: Board,
+    players: Vec<Player>,
+}
+
+impl Custom {
+    pub fn from_rows(board: Board, rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        Ok(Self {
+            players: Self::players_from_rows(&board, rows, duplicates)?,
+            board,
+        })
+    }
+}
+
+impl Game for Custom {
+    fn board(&self) -> &Board {
+        &self.board
+    }
+
+    fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    fn players_mut(&mut self) -> &mut [Player] {
+        &mut self.players
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::{Bonus, Bonuses, Line, Wagers};
+    use crate::{Key, spreadsheet::Name};
+    use eframe::egui::Color32;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_score_with_custom_board() {
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]);
+
+        let mut game = Custom::from_rows(
+            board,
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YN YP"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
+        .unwrap();
+
+        let key = Key::from_str("YY NY").unwrap();
+
+        game.play(&key).unwrap();
+
+        let player = &game.players[0];
+
+        assert_eq!(-20, player.score);
+    }
+
+    #[test]
+    fn should_add_line_bonuses() {
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]).with_bonuses(Bonuses {
+            row: 100,
+            column: 0,
+            diagonal: 0,
+            blackout: 1000,
+        });
+
+        let mut game = Custom::from_rows(
+            board,
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YN YN"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
+        .unwrap();
+
+        let key = Key::from_str("YN YY").unwrap();
+
+        game.play(&key).unwrap();
+
+        let player = &game.players[0];
+
+        assert_eq!(
+            vec![Bonus {
+                line: Line::Row(0),
+                points: 100
+            }],
+            player.card.bonuses
+        );
+        assert_eq!(110, player.score);
+    }
+
+    #[test]
+    fn should_reject_too_many_wagers() {
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]).with_wagers(Wagers {
+            multiplier: 2,
+            limit: 1,
+        });
+
+        let game = Custom::from_rows(
+            board,
+            &[Row {
+                num: 3,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("Y!N! YN"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        );
+
+        assert!(matches!(
+            game,
+            Err(Error::TooManyWagers {
+                row: 3,
+                amount: 2,
+                limit: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn should_reject_too_many_passes() {
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]).with_max_passes(1);
+
+        let game = Custom::from_rows(
+            board,
+            &[Row {
+                num: 2,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("PP YN"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        );
+
+        assert!(matches!(
+            game,
+            Err(Error::TooManyPasses {
+                row: 2,
+                amount: 2,
+                limit: 1,
+                ..
+            })
+        ));
+    }
+}
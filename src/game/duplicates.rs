@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+use crate::error::Error;
+use crate::identity;
+use crate::spreadsheet::Row;
+
+/// What to do when a name guessed more than once. Names are compared with [`identity::normalize`], and aliases only
+/// count once the rows have gone through [`identity::Aliases::apply`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Duplicates {
+    KeepFirst,
+    /// Usually the corrected guess.
+    KeepLast,
+    /// None of the player's guesses count.
+    Exclude,
+    /// Scoring fails until all but one of the guesses are removed by hand.
+    #[default]
+    Ask,
+}
+
+impl Duplicates {
+    pub const ALL: [Self; 4] = [Self::KeepFirst, Self::KeepLast, Self::Exclude, Self::Ask];
+
+    /// The rows that are scored under this policy, in their original order.
+    ///
+    /// Fails on the first repeated name when asking.
+    pub fn resolve(self, rows: &[Row]) -> Result<Vec<Row>, Error> {
+        let mut resolved = Vec::with_capacity(rows.len());
+
+        for (idx, row) in rows.iter().enumerate() {
+            let same = |other: &Row| identity::same(other.name().text(), row.name().text());
+
+            let earlier = rows[..idx].iter().any(same);
+            let later = rows[idx + 1..].iter().any(same);
+
+            let keep = match self {
+                Self::KeepFirst => !earlier,
+                Self::KeepLast => !later,
+                Self::Exclude => !earlier && !later,
+                Self::Ask if earlier => {
+                    return Err(Error::DoubleGuesser {
+                        row: row.num(),
+                        name: row.name().text().to_string(),
+                    });
+                }
+                Self::Ask => true,
+            };
+
+            if keep {
+                resolved.push(row.clone());
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl Display for Duplicates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let duplicates = match self {
+            Self::KeepFirst => "Keep First",
+            Self::KeepLast => "Keep Last",
+            Self::Exclude => "Exclude Player",
+            Self::Ask => "Ask",
+        };
+
+        write!(f, "{duplicates}")
+    }
+}
+
+/// Rows of every name that guessed more than once, grouped by name in the order they first appear.
+#[must_use]
+pub fn groups(rows: &[Row]) -> Vec<Vec<&Row>> {
+    let mut groups: Vec<Vec<&Row>> = Vec::new();
+
+    for row in rows {
+        match groups
+            .iter_mut()
+            .find(|group| identity::same(group[0].name().text(), row.name().text()))
+        {
+            Some(group) => group.push(row),
+            None => groups.push(vec![row]),
+        }
+    }
+
+    groups.retain(|group| group.len() > 1);
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nums(rows: &[Row]) -> Vec<u32> {
+        rows.iter().map(Row::num).collect()
+    }
+
+    #[test]
+    fn should_resolve_duplicates() {
+        let rows = [
+            Row::test(1, "Rolo", "YYNN", 0),
+            Row::test(2, "Edits", "YYNN", 0),
+            Row::test(3, "Rolo", "YYNN", 0),
+            Row::test(4, "Bam", "YYNN", 0),
+            Row::test(5, "Rolo", "YYNN", 0),
+        ];
+
+        assert_eq!(
+            vec![1, 2, 4],
+            nums(&Duplicates::KeepFirst.resolve(&rows).unwrap())
+        );
+        assert_eq!(
+            vec![2, 4, 5],
+            nums(&Duplicates::KeepLast.resolve(&rows).unwrap())
+        );
+        assert_eq!(
+            vec![2, 4],
+            nums(&Duplicates::Exclude.resolve(&rows).unwrap())
+        );
+        assert!(matches!(
+            Duplicates::Ask.resolve(&rows),
+            Err(Error::DoubleGuesser { row: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn should_group_duplicates() {
+        let rows = [
+            Row::test(1, "Rolo", "YYNN", 0),
+            Row::test(2, "Edits", "YYNN", 0),
+            Row::test(3, "Rolo", "YYNN", 0),
+            Row::test(4, "Bam", "YYNN", 0),
+            Row::test(5, "Rolo", "YYNN", 0),
+        ];
+
+        let groups: Vec<Vec<u32>> = groups(&rows)
+            .iter()
+            .map(|group| group.iter().map(|row| row.num()).collect())
+            .collect();
+
+        assert_eq!(vec![vec![1, 3, 5]], groups);
+    }
+
+    #[test]
+    fn should_match_normalized_names() {
+        let rows = [
+            Row::test(1, "Rolo", "YYNN", 0),
+            Row::test(2, "Edits", "YYNN", 0),
+            Row::test(3, " rоlо", "YYNN", 0),
+            Row::test(4, "Bam", "YYNN", 0),
+            Row::test(5, "ROLO", "YYNN", 0),
+        ];
+
+        assert_eq!(
+            vec![2, 4],
+            nums(&Duplicates::Exclude.resolve(&rows).unwrap())
+        );
+    }
+}
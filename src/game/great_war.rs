@@ -1,14 +1,26 @@
-use crate::{Player, board, error::Error, game::Game, spreadsheet::Row};
+use crate::{
+    Player, board,
+    error::Error,
+    game::{
+        Game,
+        board::{self, Board},
+        duplicates::Duplicates,
+    },
+    spreadsheet::Row,
+};
 
 #[derive(Debug)]
 pub struct GreatWar {
+    board: Board,
     players: Vec<Player>,
 }
 
-impl Game for GreatWar {
-    const SQUARES: usize = 78;
+impl GreatWar {
+    pub const NAME: &str = "Great War";
+
+    pub const SQUARES: usize = 78;
 
-    const BOARD: &[i32] = board![
+    pub const BOARD: &[&[i32]] = board![
         [15, 30, 50, 15, 30, 50, 15, 30],
         [50, 15, 30, 50, 15, 30, 50, 15],
         [30, 50, 15, 30, 50, 15, 30, 50],
@@ -21,15 +33,21 @@ impl Game for GreatWar {
         [70, 70, 70, 70, 70, 70, 70],
     ];
 
-    fn from_rows(rows: &[Row]) -> Result<Self, Error>
-    where
-        Self: std::marker::Sized,
-    {
-        const { assert!(Self::SQUARES == Self::BOARD.len()) }
+    pub fn from_rows(rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        const { assert!(Self::SQUARES == board::squares(Self::BOARD)) }
+        let board = Board::new(Self::NAME, Self::BOARD);
+
         Ok(Self {
-            players: Self::players_from_rows(rows)?,
+            players: Self::players_from_rows(&board, rows, duplicates)?,
+            board,
         })
     }
+}
+
+impl Game for GreatWar {
+    fn board(&self) -> &Board {
+        &self.board
+    }
 
     fn players(&self) -> &[Player] {
         &self.players
@@ -76,17 +94,21 @@ Y N N N N N N";
 
     #[test]
     fn should_score_2990() {
-        let mut game = GreatWar::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: String::from(GUESS),
-            starting_score: 0,
-        }])
+        let mut game = GreatWar::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from(GUESS),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str(KEY).unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -101,17 +123,21 @@ Y N N N N N N";
             .map(|char| if char == 'Y' { 'N' } else { 'Y' })
             .collect();
 
-        let mut game = GreatWar::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess,
-            starting_score: 0,
-        }])
+        let mut game = GreatWar::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess,
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str(KEY).unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -120,17 +146,21 @@ Y N N N N N N";
 
     #[test]
     fn should_score_max() {
-        let mut game = GreatWar::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: "Y".repeat(78),
-            starting_score: 450,
-        }])
+        let mut game = GreatWar::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: "Y".repeat(78),
+                starting_score: 450,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str(&"Y".repeat(78)).unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -138,17 +168,21 @@ Y N N N N N N";
     }
     #[test]
     fn should_score_min() {
-        let mut game = GreatWar::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: "N".repeat(78),
-            starting_score: -450,
-        }])
+        let mut game = GreatWar::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: "N".repeat(78),
+                starting_score: -450,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str(&"Y".repeat(78)).unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
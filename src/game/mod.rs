@@ -1,23 +1,36 @@
 use std::fmt::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use resvg::tiny_skia::Pixmap;
 use resvg::usvg::{Options, Tree};
 
-use crate::error::Error;
+use crate::error::{Error, KeyError};
 use crate::spreadsheet::Row;
-use crate::{Guess, Key, Player, Square};
+use crate::{Guess, Key, Player};
 
+use board::Board;
+use duplicates::Duplicates;
+use score_card::{Outcome, ScoreCard};
+use standings::Standings;
+
+pub mod board;
+pub mod custom;
+pub mod duplicates;
 pub mod great_war;
 pub mod normal;
+pub mod score_card;
+pub mod season;
+pub mod standings;
+pub mod teams;
+pub mod validation;
 
 #[macro_export]
 macro_rules! board {
     [ $( [ $( $x:expr ),* $(,)? ] ),* $(,)? ] => {{
-        const DATA: &[i32] = &[
+        const DATA: &[&[i32]] = &[
             $(
-                $( $x ),*
+                &[ $( $x ),* ]
             ),*
         ];
         DATA
@@ -25,114 +38,118 @@ macro_rules! board {
 }
 
 pub trait Game {
-    // TODO: Block submitting if key size that does not match total squares.
-    const SQUARES: usize;
-
-    const BOARD: &[i32];
-
-    // Can fail if guessers have incorrect number of guesses
-    fn from_rows(rows: &[Row]) -> Result<Self, Error>
-    where
-        Self: std::marker::Sized;
+    fn board(&self) -> &Board;
 
     fn players(&self) -> &[Player];
 
     fn players_mut(&mut self) -> &mut [Player];
 
-    fn play(&mut self, key: &Key) {
-        for player in self.players_mut() {
-            for (square, (guess, key)) in player.guess.iter().zip(key).enumerate() {
-                if guess == &Square::Pass {
-                    continue;
-                }
+    /// Fails without scoring when the key doesn't fit the board, see [`Key::check`].
+    fn play(&mut self, key: &Key) -> Result<(), KeyError> {
+        key.check(self.board())?;
 
-                if guess == key {
-                    player.score += Self::BOARD[square];
-                } else {
-                    player.score -= Self::BOARD[square];
-                }
-            }
+        // The board is tiny, cloning it lets the players be borrowed mutably while scoring.
+        let board = self.board().clone();
+
+        // Scoring always starts over from the starting score, so the same players can be rescored as more of the key
+        // is resolved, with the standings shifting accordingly.
+        for (place, player) in self.players_mut().iter_mut().enumerate() {
+            player.previous_place = (!player.card.marks.is_empty()).then_some(place);
+            player.card = ScoreCard::new(&board, &player.guess, key);
+            player.score = player.starting_score + player.card.points();
         }
 
-        // Sort by score, and when the scores match, by name.
-        self.players_mut()
-            .sort_by(|a, b| b.score.cmp(&a.score).then(a.name.cmp(&b.name)));
+        self.rank(Standings::default());
+
+        Ok(())
     }
 
-    fn players_from_rows(rows: &[Row]) -> Result<Vec<Player>, Error> {
-        let mut players = Vec::with_capacity(rows.len());
-
-        for row in rows {
-            let Ok(guess) = Guess::from_str(row.guess());
-
-            // if guess.len() != Self::SQUARES {
-            //     return Err(Error::NotEnoughValidSquares {
-            //         name: row.name().text().to_string(),
-            //         row: row.num(),
-            //         amount: guess.len(),
-            //         needed: Self::SQUARES,
-            //     });
-            // }
-
-            let player = Player {
-                name: row.name().text().to_string(),
-                color: row.name().color().to_hex(),
-                guess,
-                score: row.starting_score(),
-            };
-
-            // NOTE: Could use a `HashSet`, but given the small number of players it shouldn't matter.
-            // `HashSet::insert` also takes ownership and drops the inserted value if it already exists.
-            // For nicer error messages, its more convenient to be able to make a `Vec` of the double guessers use that
-            // to inform the user everyone who offended.
-            //
-            // Another option could be to just silently replace with new guess, or even exclude them altogether.
-            // if players.contains(&player) {
-            //     return Err(Error::DoubleGuesser { row: row.num(), name: row.name().text().to_string() });
-            // }
-
-            players.push(player);
+    /// Orders the players and assigns their ranks, breaking ties as chosen.
+    fn rank(&mut self, standings: Standings) {
+        standings.rank(self.players_mut());
+    }
+
+    /// Fails on the first guess that passes or wagers on more squares than the board allows, or on the first repeated
+    /// name when asking what to do with duplicates. See [`validation::validate`] for every problem at once.
+    fn players_from_rows(
+        board: &Board,
+        rows: &[Row],
+        duplicates: Duplicates,
+    ) -> Result<Vec<Player>, Error>
+    where
+        Self: std::marker::Sized,
+    {
+        let rows = duplicates.resolve(rows)?;
+
+        if let Some(err) = validation::validate(board, &rows, duplicates).into_error() {
+            return Err(err);
         }
 
+        let players = rows
+            .iter()
+            .map(|row| {
+                let Ok(guess) = Guess::from_str(row.guess());
+
+                Player {
+                    row: row.num(),
+                    name: row.name().text().to_string(),
+                    color: row.name().color().to_hex(),
+                    guess,
+                    starting_score: row.starting_score(),
+                    score: row.starting_score(),
+                    card: ScoreCard::default(),
+                    previous_place: None,
+                    rank: 0,
+                }
+            })
+            .collect();
+
         Ok(players)
     }
 
     fn save_png(&self, path: &Path) {
-        let path = path.with_extension("png");
-        let svg = svg(self.players());
-
-        std::thread::spawn(move || {
-            let mut opt = Options::default();
-
-            opt.fontdb_mut()
-                .load_font_data(super::fonts::GGSANS.to_vec());
-            opt.fontdb_mut()
-                .load_font_data(super::fonts::SEGUIHIS.to_vec());
-            opt.fontdb_mut()
-                .load_font_data(super::fonts::SEGUISYM.to_vec());
-            opt.fontdb_mut()
-                .load_font_data(super::fonts::NOTO_SANS_CHINENSE.to_vec());
-            opt.fontdb_mut()
-                .load_font_data(super::fonts::NOTO_SANS_KOREAN.to_vec());
-
-            let tree = Tree::from_str(&svg, &opt).expect("Invalid SVG");
-
-            let size = tree.size();
-
-            let mut pixelmap = Pixmap::new(size.width() as u32, size.height() as u32)
-                .expect("Faild to create Pixmap");
-
-            resvg::render(
-                &tree,
-                resvg::usvg::Transform::default(),
-                &mut pixelmap.as_mut(),
-            );
-
-            pixelmap.save_png(path).expect("Failed to save PNG");
-        });
+        render_png(svg(self.players()), path.with_extension("png"));
+    }
+
+    /// Writes the per-square breakdown of every player next to `path`, as `<name>.scores.csv`.
+    fn save_csv(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path.with_extension("scores.csv"), csv(self.players()))
     }
 }
 
+/// Renders the svg to a png in the background, so the UI doesn't freeze.
+pub(crate) fn render_png(svg: String, path: PathBuf) {
+    std::thread::spawn(move || {
+        let mut opt = Options::default();
+
+        opt.fontdb_mut()
+            .load_font_data(super::fonts::GGSANS.to_vec());
+        opt.fontdb_mut()
+            .load_font_data(super::fonts::SEGUIHIS.to_vec());
+        opt.fontdb_mut()
+            .load_font_data(super::fonts::SEGUISYM.to_vec());
+        opt.fontdb_mut()
+            .load_font_data(super::fonts::NOTO_SANS_CHINENSE.to_vec());
+        opt.fontdb_mut()
+            .load_font_data(super::fonts::NOTO_SANS_KOREAN.to_vec());
+
+        let tree = Tree::from_str(&svg, &opt).expect("Invalid SVG");
+
+        let size = tree.size();
+
+        let mut pixelmap =
+            Pixmap::new(size.width() as u32, size.height() as u32).expect("Faild to create Pixmap");
+
+        resvg::render(
+            &tree,
+            resvg::usvg::Transform::default(),
+            &mut pixelmap.as_mut(),
+        );
+
+        pixelmap.save_png(path).expect("Failed to save PNG");
+    });
+}
+
 #[must_use]
 pub fn svg(players: &[Player]) -> String {
     let row_height = 70;
@@ -146,10 +163,32 @@ pub fn svg(players: &[Player]) -> String {
         .max()
         .unwrap_or(10);
 
-    let x = 50 + name as u32 * px + padding;
+    let rank = players
+        .iter()
+        .map(|p| p.rank.to_string().len())
+        .max()
+        .unwrap_or(1) as u32;
+
+    // Ranks are right aligned, with the names starting after them.
+    let rank_x = padding + rank * px;
+    let name_x = rank_x + px;
+
+    let x = name_x + name as u32 * px + padding;
+
+    // Only show the hit and miss tally once the players have been scored.
+    let record = players.iter().any(|p| !p.card.marks.is_empty());
+    let record_x = x + padding;
+
+    let width = if record {
+        record_x + 200 + padding
+    } else {
+        x + padding
+    };
 
-    let width = x + padding;
-    let height = row_height * players.len() as u32;
+    // The key is the same for everyone, so any card tells which squares were thrown out.
+    let voided = players.first().map(|p| p.card.voided()).unwrap_or_default();
+
+    let height = row_height * (players.len() as u32 + u32::from(!voided.is_empty()));
 
     let mut svg = String::new();
 
@@ -161,6 +200,7 @@ pub fn svg(players: &[Player]) -> String {
         <style>
             .body-bg {{ fill: {bg}; }}
             .body-text {{ font-family: gg sans,Noto Sans SC,Segoe UI Historic,Segoe UI Symbol; font-size: 36px; }}
+            .record-text {{ font-family: gg sans,Segoe UI Symbol; font-size: 28px; }}
         </style>
 
         <rect class="body-bg" width="100%" height="100%"/>
@@ -174,19 +214,110 @@ pub fn svg(players: &[Player]) -> String {
         write!(
             svg,
             r##"
-            <text class="body-text" x="{padding}" y="{}" fill="{}">{}</text>
+            <text class="body-text" x="{rank_x}" y="{}" fill="#949ba4" text-anchor="end">{}</text>
+            <text class="body-text" x="{name_x}" y="{}" fill="{}">{}</text>
             <text class="body-text" x="{x}" y="{}" fill="#e6e6e8" text-anchor="end">{}</text>
             "##,
             y + 45,
+            player.rank,
+            y + 45,
             player.color,
             player.name,
             y + 45,
             player.score
         )
         .unwrap();
+
+        if record {
+            write!(
+                svg,
+                r##"
+            <text class="record-text" x="{record_x}" y="{}" fill="#949ba4">{} ✓  {} ✗</text>
+            "##,
+                y + 45,
+                player.card.count(Outcome::Hit),
+                player.card.count(Outcome::Miss),
+            )
+            .unwrap();
+        }
+    }
+
+    if !voided.is_empty() {
+        write!(
+            svg,
+            r##"
+            <text class="record-text" x="{padding}" y="{}" fill="#949ba4">Voided squares: {}</text>
+            "##,
+            players.len() as u32 * row_height + 45,
+            squares_list(&voided),
+        )
+        .unwrap();
     }
 
     svg.push_str("</svg>");
 
     svg
 }
+
+/// Human readable, one based, list of square indices.
+#[must_use]
+pub fn squares_list(squares: &[usize]) -> String {
+    squares
+        .iter()
+        .map(|square| (square + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Comma separated table of every player, with the points of each square after the totals.
+#[must_use]
+pub fn csv(players: &[Player]) -> String {
+    let squares = players
+        .iter()
+        .map(|p| p.card.marks.len())
+        .max()
+        .unwrap_or_default();
+
+    let mut csv = String::from("Name,Score,Hits,Misses,Passes,Bonus");
+
+    for square in 1..=squares {
+        write!(csv, ",{square}").unwrap();
+    }
+
+    csv.push('\n');
+
+    for player in players {
+        write!(
+            csv,
+            "{},{},{},{},{},{}",
+            csv_field(&player.name),
+            player.score,
+            player.card.count(Outcome::Hit),
+            player.card.count(Outcome::Miss),
+            player.card.count(Outcome::Pass),
+            player
+                .card
+                .bonuses
+                .iter()
+                .map(|bonus| bonus.points)
+                .sum::<i32>(),
+        )
+        .unwrap();
+
+        for mark in &player.card.marks {
+            write!(csv, ",{}", mark.points).unwrap();
+        }
+
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -1,28 +1,46 @@
-use crate::{Player, board, error::Error, game::Game, spreadsheet::Row};
+use crate::{
+    Player, board,
+    error::Error,
+    game::{
+        Game,
+        board::{self, Board},
+        duplicates::Duplicates,
+    },
+    spreadsheet::Row,
+};
 
 #[derive(Debug)]
 pub struct Normal {
+    board: Board,
     players: Vec<Player>,
 }
 
-impl Game for Normal {
-    const SQUARES: usize = 12;
+impl Normal {
+    pub const NAME: &str = "Normal";
+
+    pub const SQUARES: usize = 12;
 
-    const BOARD: &[i32] = board![
+    pub const BOARD: &[&[i32]] = board![
         [10, 10, 10, 20], //
         [30, 30, 30, 60],
         [50, 50, 50, 100],
     ];
 
-    fn from_rows(rows: &[Row]) -> Result<Self, Error>
-    where
-        Self: std::marker::Sized,
-    {
-        const { assert!(Self::SQUARES == Self::BOARD.len()) }
+    pub fn from_rows(rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        const { assert!(Self::SQUARES == board::squares(Self::BOARD)) }
+        let board = Board::new(Self::NAME, Self::BOARD);
+
         Ok(Self {
-            players: Self::players_from_rows(rows)?,
+            players: Self::players_from_rows(&board, rows, duplicates)?,
+            board,
         })
     }
+}
+
+impl Game for Normal {
+    fn board(&self) -> &Board {
+        &self.board
+    }
 
     fn players(&self) -> &[Player] {
         &self.players
@@ -42,17 +60,21 @@ mod tests {
 
     #[test]
     fn should_score_450() {
-        let mut game = Normal::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: String::from("YYYY YYYY YYYY"),
-            starting_score: 0,
-        }])
+        let mut game = Normal::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YYYY YYYY YYYY"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str("YYYY YYYY YYYY").unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -61,17 +83,21 @@ mod tests {
 
     #[test]
     fn should_score_neg_450() {
-        let mut game = Normal::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: String::from("NNNN NNNN NNNN"),
-            starting_score: 0,
-        }])
+        let mut game = Normal::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("NNNN NNNN NNNN"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str("YYYY YYYY YYYY").unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -80,17 +106,21 @@ mod tests {
 
     #[test]
     fn should_score_with_passes() {
-        let mut game = Normal::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: String::from("YYYP YYYP YYYP"),
-            starting_score: 0,
-        }])
+        let mut game = Normal::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YYYP YYYP YYYP"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str("YYYY YYYY YYYY").unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
@@ -99,20 +129,117 @@ mod tests {
 
     #[test]
     fn should_score_with_zero() {
-        let mut game = Normal::from_rows(&[Row {
-            num: 1,
-            name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
-            guess: String::from("YNNP NYNNP NNYP"),
-            starting_score: 0,
-        }])
+        let mut game = Normal::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YNNP NYNNP NNYP"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
         .unwrap();
 
         let key = Key::from_str("YYNN NNNN NNYN").unwrap();
 
-        game.play(&key);
+        game.play(&key).unwrap();
 
         let player = &game.players[0];
 
         assert_eq!(0, player.score);
     }
+
+    #[test]
+    fn should_skip_unresolved_squares() {
+        let mut game = Normal::from_rows(
+            &[Row {
+                num: 1,
+                name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                guess: String::from("YYYY YYYY YYYY"),
+                starting_score: 0,
+                submitted: None,
+            }],
+            Duplicates::default(),
+        )
+        .unwrap();
+
+        let key = Key::from_str("YYYY ???? ????").unwrap();
+
+        game.play(&key).unwrap();
+
+        let player = &game.players[0];
+
+        assert_eq!(50, player.score);
+    }
+
+    #[test]
+    fn should_rescore_as_key_resolves() {
+        let mut game = Normal::from_rows(
+            &[
+                Row {
+                    num: 1,
+                    name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                    guess: String::from("YYYY YYYY YYYY"),
+                    starting_score: 100,
+                    submitted: None,
+                },
+                Row {
+                    num: 2,
+                    name: Name::new(String::from("Edits"), Color32::from_rgb(0, 0, 0)),
+                    guess: String::from("NNNN NNNN NNNN"),
+                    starting_score: 100,
+                    submitted: None,
+                },
+            ],
+            Duplicates::default(),
+        )
+        .unwrap();
+
+        game.play(&Key::from_str("YYYY ???? ????").unwrap())
+            .unwrap();
+
+        assert_eq!("Rolo", game.players[0].name);
+        assert_eq!(150, game.players[0].score);
+
+        game.play(&Key::from_str("YYYY NNNN NNNN").unwrap())
+            .unwrap();
+
+        assert_eq!("Edits", game.players[0].name);
+        assert_eq!(450, game.players[0].score);
+        assert_eq!(Some(1), game.players[0].previous_place);
+        assert_eq!(-250, game.players[1].score);
+    }
+
+    #[test]
+    fn should_score_voided_as_zero() {
+        let mut game = Normal::from_rows(
+            &[
+                Row {
+                    num: 1,
+                    name: Name::new(String::from("Rolo"), Color32::from_rgb(0, 0, 0)),
+                    guess: String::from("YYYY YYYY YYYY"),
+                    starting_score: 0,
+                    submitted: None,
+                },
+                Row {
+                    num: 2,
+                    name: Name::new(String::from("Edits"), Color32::from_rgb(0, 0, 0)),
+                    guess: String::from("NNNN YYYY YYYY"),
+                    starting_score: 0,
+                    submitted: None,
+                },
+            ],
+            Duplicates::default(),
+        )
+        .unwrap();
+
+        let key = Key::from_str("XXXX YYYY YYYY").unwrap();
+
+        game.play(&key).unwrap();
+
+        assert_eq!(400, game.players[0].score);
+        assert_eq!(400, game.players[1].score);
+        assert_eq!(vec![0, 1, 2, 3], game.players[0].card.voided());
+    }
 }
@@ -0,0 +1,217 @@
+use std::fmt::Display;
+
+use crate::game::board::{Board, Bonus};
+use crate::{Guess, Key, Square};
+
+/// How a single square played out against the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    Hit,
+    Miss,
+    Pass,
+    /// The key has no answer for the square yet.
+    Unresolved,
+    /// The square was thrown out, and is worth nothing for everyone.
+    Void,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome = match self {
+            Self::Hit => "Hit",
+            Self::Miss => "Miss",
+            Self::Pass => "Pass",
+            Self::Unresolved => "Unresolved",
+            Self::Void => "Void",
+        };
+
+        write!(f, "{outcome}")
+    }
+}
+
+/// The result of one square for one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Mark {
+    pub guess: Square,
+    pub key: Square,
+    pub outcome: Outcome,
+    pub wagered: bool,
+    /// Points the square is worth on a hit, before any wager.
+    pub value: i32,
+    /// Points gained, or lost when negative.
+    pub points: i32,
+}
+
+/// Breakdown of how a player got their points in the last scoring.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScoreCard {
+    /// One entry per square, in reading order.
+    pub marks: Vec<Mark>,
+    /// Lines completed, on top of the points of the squares.
+    pub bonuses: Vec<Bonus>,
+}
+
+impl ScoreCard {
+    /// Marks every square of a guess against the key, and then awards the lines that were completed.
+    pub fn new(board: &Board, guess: &Guess, key: &Key) -> Self {
+        let mut marks = Vec::with_capacity(board.squares());
+
+        let values = board.rewards().zip(board.penalties());
+
+        for (square, ((&pick, &key), (reward, penalty))) in
+            guess.iter().zip(key).zip(values).enumerate()
+        {
+            let wagered = guess.is_wagered(square);
+            let multiplier = if wagered {
+                board.wagers().multiplier
+            } else {
+                1
+            };
+
+            let (outcome, points) = if key == Square::Unresolved {
+                (Outcome::Unresolved, 0)
+            } else if key == Square::Void {
+                (Outcome::Void, 0)
+            } else if pick == Square::Pass {
+                (Outcome::Pass, board.pass())
+            } else if pick == key {
+                (Outcome::Hit, reward * multiplier)
+            } else {
+                (Outcome::Miss, -penalty * multiplier)
+            };
+
+            marks.push(Mark {
+                guess: pick,
+                key,
+                outcome,
+                wagered,
+                value: reward,
+                points,
+            });
+        }
+
+        let mut card = Self {
+            marks,
+            bonuses: Vec::new(),
+        };
+
+        card.bonuses = board.line_bonuses(&card.outcomes());
+
+        card
+    }
+
+    /// Total points gained or lost, including bonuses.
+    pub fn points(&self) -> i32 {
+        self.marks.iter().map(|mark| mark.points).sum::<i32>()
+            + self.bonuses.iter().map(|bonus| bonus.points).sum::<i32>()
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.marks
+            .iter()
+            .filter(|mark| mark.outcome == outcome)
+            .count()
+    }
+
+    /// Indices of the squares that were thrown out.
+    pub fn voided(&self) -> Vec<usize> {
+        self.marks
+            .iter()
+            .enumerate()
+            .filter(|(_, mark)| mark.outcome == Outcome::Void)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub fn outcomes(&self) -> Vec<Outcome> {
+        self.marks.iter().map(|mark| mark.outcome).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::Wagers;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_mark_each_square() {
+        let board = Board::new("Mini", &[&[10, 20, 30]]);
+        let Ok(guess) = Guess::from_str("YNP");
+        let key = Key::from_str("YYY").unwrap();
+
+        let card = ScoreCard::new(&board, &guess, &key);
+
+        assert_eq!(
+            vec![
+                Mark {
+                    guess: Square::Yes,
+                    key: Square::Yes,
+                    outcome: Outcome::Hit,
+                    wagered: false,
+                    value: 10,
+                    points: 10,
+                },
+                Mark {
+                    guess: Square::No,
+                    key: Square::Yes,
+                    outcome: Outcome::Miss,
+                    wagered: false,
+                    value: 20,
+                    points: -20,
+                },
+                Mark {
+                    guess: Square::Pass,
+                    key: Square::Yes,
+                    outcome: Outcome::Pass,
+                    wagered: false,
+                    value: 30,
+                    points: 0,
+                },
+            ],
+            card.marks
+        );
+        assert_eq!(-10, card.points());
+    }
+
+    #[test]
+    fn should_use_separate_penalties_and_pass() {
+        let board = Board::new("Mini", &[&[10, 20, 30]])
+            .with_penalties(&[&[0, 5, 60]])
+            .with_pass(-3);
+        let Ok(guess) = Guess::from_str("NNP");
+        let key = Key::from_str("YYY").unwrap();
+
+        let card = ScoreCard::new(&board, &guess, &key);
+
+        assert_eq!(
+            vec![0, -5, -3],
+            card.marks
+                .iter()
+                .map(|mark| mark.points)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_multiply_wagered_squares() {
+        let board = Board::new("Mini", &[&[10, 20, 30]]).with_wagers(Wagers {
+            multiplier: 3,
+            limit: 2,
+        });
+        let Ok(guess) = Guess::from_str("Y! N! P!");
+        let key = Key::from_str("YYY").unwrap();
+
+        let card = ScoreCard::new(&board, &guess, &key);
+
+        assert_eq!(2, guess.wagers());
+        assert_eq!("Y!N!P", guess.to_string());
+        assert_eq!(
+            vec![30, -60, 0],
+            card.marks
+                .iter()
+                .map(|mark| mark.points)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::game::board::Board;
+use crate::game::standings::Standings;
+use crate::spreadsheet::{Row, Week};
+use crate::{Bingo, Key, Player, identity};
+
+/// Standings of a workbook with a sheet per week, with every player's total carried from one week to the next.
+#[derive(Debug)]
+pub struct Season {
+    /// Weeks that have a key, in order.
+    pub weeks: Vec<Played>,
+    /// Everyone who played in any week, by their total after the last week. `previous_place` is their place the week
+    /// before.
+    pub total: Vec<Player>,
+    /// Board of the first week, which all weeks are played on.
+    pub board: Option<Board>,
+}
+
+/// A week that has been scored.
+#[derive(Debug)]
+pub struct Played {
+    /// Name of the week's sheet.
+    pub name: String,
+    /// Players by the points they scored that week alone.
+    pub standings: Vec<Player>,
+}
+
+impl Season {
+    /// Scores every week with a key, in order, on the game made by `new`. Weeks without a key haven't been played yet,
+    /// and are skipped.
+    ///
+    /// Players are matched across weeks by name, see [`identity::normalize`]. Their starting score is their total so
+    /// far, and only players that are new to the season start from the score in their row.
+    ///
+    /// Aliases aren't looked up here. Players who changed their name are only matched when each week's rows were first
+    /// passed through [`Aliases::apply`](crate::identity::Aliases::apply).
+    pub fn play(
+        weeks: &[Week],
+        standings: Standings,
+        new: impl Fn(&[Row]) -> Result<Bingo, Error>,
+    ) -> Result<Self, Error> {
+        let mut season = Self {
+            weeks: Vec::new(),
+            total: Vec::new(),
+            board: None,
+        };
+
+        for week in weeks {
+            let Some(key) = &week.key else {
+                continue;
+            };
+
+            let rows: Vec<Row> = week
+                .rows
+                .iter()
+                .map(|row| Row {
+                    starting_score: season
+                        .total
+                        .iter()
+                        .find(|player| identity::same(&player.name, row.name().text()))
+                        .map_or(row.starting_score, |player| player.score),
+                    ..row.clone()
+                })
+                .collect();
+
+            let in_week = |error| Error::InWeek {
+                week: week.name.clone(),
+                error: Box::new(error),
+            };
+
+            let key = Key::from_str(key).map_err(|err| in_week(Error::InvalidKey(err)))?;
+            let mut bingo = new(&rows).map_err(in_week)?;
+
+            bingo
+                .play(&key)
+                .map_err(|err| in_week(Error::InvalidKey(err)))?;
+
+            season.board.get_or_insert_with(|| bingo.board().clone());
+
+            let mut weekly: Vec<Player> = bingo
+                .players()
+                .iter()
+                .map(|player| Player {
+                    starting_score: 0,
+                    score: player.card.points(),
+                    ..player.clone()
+                })
+                .collect();
+
+            standings.rank(&mut weekly);
+
+            let previous: Vec<String> = season
+                .total
+                .iter()
+                .map(|player| player.name.clone())
+                .collect();
+
+            for player in bingo.players() {
+                match season.total.iter_mut().find(|total| *total == player) {
+                    Some(total) => *total = player.clone(),
+                    None => season.total.push(player.clone()),
+                }
+            }
+
+            for player in &mut season.total {
+                player.previous_place = previous
+                    .iter()
+                    .position(|name| identity::same(name, &player.name));
+            }
+
+            standings.rank(&mut season.total);
+
+            season.weeks.push(Played {
+                name: week.name.clone(),
+                standings: weekly,
+            });
+        }
+
+        Ok(season)
+    }
+
+    /// Orders the weekly and total standings again, breaking ties as chosen.
+    pub fn rank(&mut self, standings: Standings) {
+        for week in &mut self.weeks {
+            standings.rank(&mut week.standings);
+        }
+
+        standings.rank(&mut self.total);
+    }
+
+    /// Saves the total standings next to `path`, as `<name>.png`, and each week as `<name>.<week>.png`.
+    pub fn save_png(&self, path: &Path) {
+        super::render_png(super::svg(&self.total), path.with_extension("png"));
+
+        for week in &self.weeks {
+            super::render_png(
+                super::svg(&week.standings),
+                path.with_extension(format!("{}.png", week.name)),
+            );
+        }
+    }
+
+    /// Writes the per-square breakdown of each week next to `path`, as `<name>.<week>.scores.csv`.
+    pub fn save_csv(&self, path: &Path) -> std::io::Result<()> {
+        for week in &self.weeks {
+            std::fs::write(
+                path.with_extension(format!("{}.scores.csv", week.name)),
+                super::csv(&week.standings),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::duplicates::Duplicates;
+
+    fn scores(players: &[Player]) -> Vec<(&str, i32, Option<usize>)> {
+        players
+            .iter()
+            .map(|p| (p.name.as_str(), p.score, p.previous_place))
+            .collect()
+    }
+
+    #[test]
+    fn should_carry_totals_between_weeks() {
+        let weeks = [
+            Week {
+                name: String::from("Week 1"),
+                rows: vec![
+                    Row::test(1, "Rolo", "YYYY", 0),
+                    Row::test(2, "Edits", "NNNN", 10),
+                ],
+                key: Some(String::from("YYYY")),
+            },
+            Week {
+                name: String::from("Week 2"),
+                rows: vec![
+                    Row::test(1, "Edits", "NNNN", 999),
+                    Row::test(2, "Bam", "YNNN", 0),
+                ],
+                key: Some(String::from("NNNN")),
+            },
+            Week {
+                name: String::from("Week 3"),
+                rows: vec![Row::test(1, "Rolo", "YYYY", 0)],
+                key: None,
+            },
+        ];
+
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]);
+
+        let season = Season::play(&weeks, Standings::default(), |rows| {
+            Bingo::custom(board.clone(), rows, Duplicates::default())
+        })
+        .unwrap();
+
+        assert_eq!(2, season.weeks.len());
+        assert_eq!(
+            vec![("Rolo", 50, None), ("Edits", -50, None)],
+            scores(&season.weeks[0].standings)
+        );
+        assert_eq!(
+            vec![("Edits", 50, None), ("Bam", 40, None)],
+            scores(&season.weeks[1].standings)
+        );
+        assert_eq!(
+            vec![
+                ("Rolo", 50, Some(0)),
+                ("Bam", 40, None),
+                ("Edits", 10, Some(1))
+            ],
+            scores(&season.total)
+        );
+    }
+
+    #[test]
+    fn should_name_week_of_error() {
+        let weeks = [Week {
+            name: String::from("Week 1"),
+            rows: vec![Row::test(1, "Rolo", "PPPP", 0)],
+            key: Some(String::from("YYYY")),
+        }];
+
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]).with_max_passes(1);
+
+        let err = Season::play(&weeks, Standings::default(), |rows| {
+            Bingo::custom(board.clone(), rows, Duplicates::default())
+        })
+        .unwrap_err();
+
+        assert!(matches!(err, Error::InWeek { week, .. } if week == "Week 1"));
+    }
+}
@@ -0,0 +1,228 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use crate::Player;
+use crate::game::score_card::Outcome;
+
+/// How players on the same score are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Players on the same score share a rank.
+    #[default]
+    Shared,
+    FewestPasses,
+    /// Compares the most valuable hits first, then the next most valuable, and so on. Squares are valued by the board,
+    /// so a wager doesn't make a square more valuable.
+    MostHighValueHits,
+    /// Whoever is higher up in the spreadsheet, usually whoever submitted first.
+    EarliestRow,
+}
+
+impl TieBreak {
+    pub const ALL: [Self; 4] = [
+        Self::Shared,
+        Self::FewestPasses,
+        Self::MostHighValueHits,
+        Self::EarliestRow,
+    ];
+
+    fn cmp(self, a: &Player, b: &Player) -> Ordering {
+        match self {
+            Self::Shared => Ordering::Equal,
+            Self::FewestPasses => a.guess.passes().cmp(&b.guess.passes()),
+            Self::MostHighValueHits => high_value_hits(b).cmp(&high_value_hits(a)),
+            Self::EarliestRow => a.row.cmp(&b.row),
+        }
+    }
+}
+
+impl Display for TieBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tie_break = match self {
+            Self::Shared => "Shared Rank",
+            Self::FewestPasses => "Fewest Passes",
+            Self::MostHighValueHits => "Most High Value Hits",
+            Self::EarliestRow => "Earliest Row",
+        };
+
+        write!(f, "{tie_break}")
+    }
+}
+
+/// How rank numbers continue after players that are tied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Ranking {
+    /// `1, 1, 3`
+    #[default]
+    Competition,
+    /// `1, 1, 2`
+    Dense,
+}
+
+impl Ranking {
+    pub const ALL: [Self; 2] = [Self::Competition, Self::Dense];
+}
+
+impl Display for Ranking {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranking = match self {
+            Self::Competition => "Competition (1, 1, 3)",
+            Self::Dense => "Dense (1, 1, 2)",
+        };
+
+        write!(f, "{ranking}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Standings {
+    pub tie_break: TieBreak,
+    pub ranking: Ranking,
+}
+
+impl Standings {
+    /// Sorts players by score and the tie-break, and gives each one their rank.
+    ///
+    /// Players that are still tied after the tie-break share a rank, and are listed by name.
+    pub fn rank(self, players: &mut [Player]) {
+        let order =
+            |a: &Player, b: &Player| b.score.cmp(&a.score).then_with(|| self.tie_break.cmp(a, b));
+
+        players.sort_by(|a, b| order(a, b).then_with(|| a.name.cmp(&b.name)));
+
+        let mut rank = 0;
+
+        for idx in 0..players.len() {
+            let shared = idx > 0 && order(&players[idx - 1], &players[idx]) == Ordering::Equal;
+
+            if !shared {
+                rank = match self.ranking {
+                    Ranking::Competition => idx + 1,
+                    Ranking::Dense => rank + 1,
+                };
+            }
+
+            players[idx].rank = rank;
+        }
+    }
+}
+
+/// Board values of every hit, most valuable first.
+fn high_value_hits(player: &Player) -> Vec<i32> {
+    let mut hits: Vec<i32> = player
+        .card
+        .marks
+        .iter()
+        .filter(|mark| mark.outcome == Outcome::Hit)
+        .map(|mark| mark.value)
+        .collect();
+
+    hits.sort_unstable_by(|a, b| b.cmp(a));
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+    use crate::game::board::{Board, Wagers};
+    use crate::game::score_card::ScoreCard;
+    use std::str::FromStr;
+
+    fn ranks(players: &[Player]) -> Vec<(&str, usize)> {
+        players.iter().map(|p| (p.name.as_str(), p.rank)).collect()
+    }
+
+    #[test]
+    fn should_share_competition_ranks() {
+        let mut players = [
+            Player::test(1, "Edits", "#f2f3f5", "YYY", 10),
+            Player::test(2, "Rolo", "#f2f3f5", "YYP", 20),
+            Player::test(3, "Bam", "#f2f3f5", "YPP", 20),
+            Player::test(4, "Khun", "#f2f3f5", "NNN", 5),
+        ];
+
+        Standings::default().rank(&mut players);
+
+        assert_eq!(
+            vec![("Bam", 1), ("Rolo", 1), ("Edits", 3), ("Khun", 4)],
+            ranks(&players)
+        );
+    }
+
+    #[test]
+    fn should_share_dense_ranks() {
+        let mut players = [
+            Player::test(1, "Edits", "#f2f3f5", "YYY", 10),
+            Player::test(2, "Rolo", "#f2f3f5", "YYP", 20),
+            Player::test(3, "Bam", "#f2f3f5", "YPP", 20),
+        ];
+
+        Standings {
+            tie_break: TieBreak::Shared,
+            ranking: Ranking::Dense,
+        }
+        .rank(&mut players);
+
+        assert_eq!(vec![("Bam", 1), ("Rolo", 1), ("Edits", 2)], ranks(&players));
+    }
+
+    #[test]
+    fn should_break_ties_by_fewest_passes() {
+        let mut players = [
+            Player::test(2, "Bam", "#f2f3f5", "YPP", 20),
+            Player::test(1, "Rolo", "#f2f3f5", "YYP", 20),
+        ];
+
+        Standings {
+            tie_break: TieBreak::FewestPasses,
+            ranking: Ranking::Competition,
+        }
+        .rank(&mut players);
+
+        assert_eq!(vec![("Rolo", 1), ("Bam", 2)], ranks(&players));
+    }
+
+    #[test]
+    fn should_break_ties_by_earliest_row() {
+        let mut players = [
+            Player::test(2, "Bam", "#f2f3f5", "YYY", 20),
+            Player::test(7, "Aaa", "#f2f3f5", "YYY", 20),
+        ];
+
+        Standings {
+            tie_break: TieBreak::EarliestRow,
+            ranking: Ranking::Competition,
+        }
+        .rank(&mut players);
+
+        assert_eq!(vec![("Bam", 1), ("Aaa", 2)], ranks(&players));
+    }
+
+    #[test]
+    fn should_break_ties_by_board_value_of_hits() {
+        let board = Board::new("Mini", &[&[10, 30]]).with_wagers(Wagers {
+            multiplier: 4,
+            limit: 1,
+        });
+        let key = Key::from_str("YN").unwrap();
+
+        // A wagered 10 is worth 40, which is more than the 30, but the 30 is the more valuable square.
+        let mut wagered = Player::test(1, "Rolo", "#f2f3f5", "Y! Y", 40);
+        wagered.card = ScoreCard::new(&board, &wagered.guess, &key);
+
+        let mut high = Player::test(2, "Bam", "#f2f3f5", "PN", 40);
+        high.card = ScoreCard::new(&board, &high.guess, &key);
+
+        let mut players = [wagered, high];
+
+        Standings {
+            tie_break: TieBreak::MostHighValueHits,
+            ranking: Ranking::Competition,
+        }
+        .rank(&mut players);
+
+        assert_eq!(vec![("Bam", 1), ("Rolo", 2)], ranks(&players));
+    }
+}
@@ -0,0 +1,349 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use eframe::egui::Color32;
+use serde::Deserialize;
+
+use crate::error::TeamError;
+use crate::{Player, identity};
+
+pub static RED_WITCH: &[u8] = include_bytes!("../../assets/red_witch.png");
+pub static SILVER_DWARF: &[u8] = include_bytes!("../../assets/silver_dwarf.png");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crest {
+    RedWitch,
+    SilverDwarf,
+}
+
+impl Crest {
+    pub fn png(self) -> &'static [u8] {
+        match self {
+            Self::RedWitch => RED_WITCH,
+            Self::SilverDwarf => SILVER_DWARF,
+        }
+    }
+}
+
+/// Players grouped into a faction, with their combined score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Team {
+    pub name: String,
+    pub color: String,
+    pub crest: Option<Crest>,
+    pub members: Vec<String>,
+    pub total: i32,
+}
+
+impl Team {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average(&self) -> f64 {
+        if self.members.is_empty() {
+            return 0.0;
+        }
+
+        f64::from(self.total) / self.members.len() as f64
+    }
+}
+
+/// Groups players by the colour of their name, which is their Discord role colour.
+///
+/// Teams are named after their colour, and sorted by total score.
+#[must_use]
+pub fn by_color(players: &[Player]) -> Vec<Team> {
+    let mut teams: Vec<Team> = Vec::new();
+
+    for player in players {
+        let color = Color32::from_hex(&player.color).ok();
+
+        match teams
+            .iter_mut()
+            .find(|team| Color32::from_hex(&team.color).ok() == color)
+        {
+            Some(team) => {
+                team.members.push(player.name.clone());
+                team.total += player.score;
+            }
+            None => teams.push(Team {
+                name: player.color.clone(),
+                color: player.color.clone(),
+                crest: None,
+                members: vec![player.name.clone()],
+                total: player.score,
+            }),
+        }
+    }
+
+    sort(&mut teams);
+
+    teams
+}
+
+/// Explicit team definitions, loaded from a `toml` file:
+///
+/// ```toml
+/// [[team]]
+/// name = "Red Witch"
+/// crest = "red_witch"
+/// # Players with this name colour join the team.
+/// color = "#ed4245"
+/// # Players that join the team regardless of their colour.
+/// members = ["Rolo"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TeamMap {
+    #[serde(rename = "team", default)]
+    teams: Vec<TeamEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TeamEntry {
+    name: String,
+    color: Option<String>,
+    crest: Option<Crest>,
+    #[serde(default)]
+    members: Vec<String>,
+}
+
+impl TeamMap {
+    pub fn load(path: &Path) -> Result<Self, TeamError> {
+        let contents = fs::read_to_string(path).map_err(|err| TeamError::Io {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })?;
+
+        toml::from_str(&contents).map_err(|err| TeamError::Parse {
+            path: path.to_path_buf(),
+            reason: err.message().to_string(),
+        })
+    }
+
+    /// Puts every player in the first team that lists them by name, compared as in [`identity::same`], or failing
+    /// that, the first team with their colour. Players that match no team are left out.
+    #[must_use]
+    pub fn teams(&self, players: &[Player]) -> Vec<Team> {
+        let mut teams: Vec<Team> = self
+            .teams
+            .iter()
+            .map(|entry| Team {
+                name: entry.name.clone(),
+                color: entry
+                    .color
+                    .clone()
+                    .unwrap_or_else(|| String::from("#f2f3f5")),
+                crest: entry.crest,
+                members: Vec::new(),
+                total: 0,
+            })
+            .collect();
+
+        for player in players {
+            let by_name = self.teams.iter().position(|entry| {
+                entry
+                    .members
+                    .iter()
+                    .any(|member| identity::same(member, &player.name))
+            });
+
+            let by_color = || {
+                let color = Color32::from_hex(&player.color).ok();
+
+                self.teams.iter().position(|entry| {
+                    entry
+                        .color
+                        .as_deref()
+                        .is_some_and(|hex| Color32::from_hex(hex).ok() == color)
+                })
+            };
+
+            if let Some(idx) = by_name.or_else(by_color) {
+                teams[idx].members.push(player.name.clone());
+                teams[idx].total += player.score;
+            }
+        }
+
+        sort(&mut teams);
+
+        teams
+    }
+}
+
+fn sort(teams: &mut [Team]) {
+    teams.sort_by(|a, b| b.total.cmp(&a.total).then(a.name.cmp(&b.name)));
+}
+
+/// Saves the team leaderboard next to `path`, as `<name>.teams.png`.
+pub fn save_png(teams: &[Team], path: &Path) {
+    super::render_png(svg(teams), path.with_extension("teams.png"));
+}
+
+/// Team leaderboard, in the same style as the player one.
+#[must_use]
+pub fn svg(teams: &[Team]) -> String {
+    let row_height = 70;
+
+    let px = 20;
+    let padding = 50;
+    let crest_size = 40;
+
+    let name = teams
+        .iter()
+        .map(|t| t.name.chars().count())
+        .max()
+        .unwrap_or(10);
+
+    let name_x = padding + crest_size + px;
+    let total_x = name_x + name as u32 * px + padding;
+    let average_x = total_x + 220;
+
+    let width = average_x + padding;
+    let height = row_height * teams.len() as u32;
+
+    let mut svg = String::new();
+
+    let bg = "#222226";
+
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}">
+        <style>
+            .body-bg {{ fill: {bg}; }}
+            .body-text {{ font-family: gg sans,Noto Sans SC,Segoe UI Historic,Segoe UI Symbol; font-size: 36px; }}
+            .record-text {{ font-family: gg sans,Segoe UI Symbol; font-size: 28px; }}
+        </style>
+
+        <rect class="body-bg" width="100%" height="100%"/>
+    "#
+    )
+    .unwrap();
+
+    for (idx, team) in teams.iter().enumerate() {
+        let y = idx as u32 * row_height;
+
+        if let Some(crest) = team.crest {
+            write!(
+                svg,
+                r#"
+            <image x="{padding}" y="{}" width="{crest_size}" height="{crest_size}" href="data:image/png;base64,{}"/>
+            "#,
+                y + 15,
+                STANDARD.encode(crest.png()),
+            )
+            .unwrap();
+        }
+
+        write!(
+            svg,
+            r##"
+            <text class="body-text" x="{name_x}" y="{}" fill="{}">{}</text>
+            <text class="body-text" x="{total_x}" y="{}" fill="#e6e6e8" text-anchor="end">{}</text>
+            <text class="record-text" x="{average_x}" y="{}" fill="#949ba4" text-anchor="end">avg {:.1}</text>
+            "##,
+            y + 45,
+            team.color,
+            team.name,
+            y + 45,
+            team.total,
+            y + 45,
+            team.average(),
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_group_by_color() {
+        let players = [
+            Player::test(1, "Rolo", "#ed4245ff", "", 30),
+            Player::test(2, "Edits", "#c0c0c0ff", "", 50),
+            Player::test(3, "Bam", "#ed4245", "", 10),
+        ];
+
+        let teams = by_color(&players);
+
+        assert_eq!(2, teams.len());
+        assert_eq!(vec!["Edits"], teams[0].members);
+        assert_eq!(vec!["Rolo", "Bam"], teams[1].members);
+        assert_eq!(40, teams[1].total);
+        assert!((teams[1].average() - 20.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn should_group_by_map() {
+        let map: TeamMap = toml::from_str(
+            r##"
+[[team]]
+name = "Red Witch"
+crest = "red_witch"
+color = "#ed4245"
+
+[[team]]
+name = "Silver Dwarf"
+crest = "silver_dwarf"
+members = ["Bam"]
+"##,
+        )
+        .unwrap();
+
+        let players = [
+            Player::test(1, "Rolo", "#ed4245ff", "", 30),
+            Player::test(2, "Edits", "#c0c0c0ff", "", 50),
+            Player::test(3, "Bam", "#ed4245ff", "", 10),
+        ];
+
+        let teams = map.teams(&players);
+
+        assert_eq!("Red Witch", teams[0].name);
+        assert_eq!(Some(Crest::RedWitch), teams[0].crest);
+        assert_eq!(vec!["Rolo"], teams[0].members);
+        assert_eq!(vec!["Bam"], teams[1].members);
+    }
+
+    #[test]
+    fn should_match_members_however_written() {
+        let map: TeamMap = toml::from_str(
+            r#"
+[[team]]
+name = "Silver Dwarf"
+members = [" ROLO", "Ｅｄｉｔｓ"]
+"#,
+        )
+        .unwrap();
+
+        let players = [
+            Player::test(1, "Rolo", "#ed4245ff", "", 30),
+            Player::test(2, "edits", "#c0c0c0ff", "", 50),
+        ];
+
+        assert_eq!(vec!["Rolo", "edits"], map.teams(&players)[0].members);
+    }
+
+    #[test]
+    fn should_embed_crests() {
+        let team = Team {
+            name: String::from("Red Witch"),
+            color: String::from("#ed4245"),
+            crest: Some(Crest::RedWitch),
+            members: vec![String::from("Rolo")],
+            total: 30,
+        };
+
+        let tree =
+            resvg::usvg::Tree::from_str(&svg(&[team]), &resvg::usvg::Options::default()).unwrap();
+
+        assert!(tree.root().has_children());
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Guess;
+use crate::error::Error;
+use crate::game::board::Board;
+use crate::game::duplicates::Duplicates;
+use crate::identity;
+use crate::spreadsheet::Row;
+
+/// How much a problem with a guess matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The guess is scored anyway, but is likely a mistake.
+    Warning,
+    /// The bingo can't be scored until it is fixed.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self {
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        };
+
+        write!(f, "{severity}")
+    }
+}
+
+#[derive(Debug)]
+pub struct Issue {
+    pub severity: Severity,
+    pub error: Error,
+}
+
+/// Every problem found in the guesses, rather than just the first.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// In row order.
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues of the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    /// The first issue that stops the bingo from being scored.
+    #[must_use]
+    pub fn into_error(self) -> Option<Error> {
+        self.issues
+            .into_iter()
+            .find(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.error)
+    }
+}
+
+/// Checks every row against the board.
+///
+/// Guessing for fewer squares than the board has is an error, as the guess would be scored against the wrong squares
+/// of the key, and so is passing or wagering on more squares than the board allows. Guessing for more squares is a
+/// warning, as the extra squares are left out. A name that already guessed further up is an error when asking what to
+/// do with duplicates, and a warning otherwise. Rows the duplicates policy leaves out are not checked further.
+#[must_use]
+pub fn validate(board: &Board, rows: &[Row], duplicates: Duplicates) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let kept: Vec<u32> = match duplicates.resolve(rows) {
+        Ok(kept) => kept.iter().map(Row::num).collect(),
+        Err(_) => rows.iter().map(Row::num).collect(),
+    };
+
+    for (idx, row) in rows.iter().enumerate() {
+        let Ok(guess) = Guess::from_str(row.guess());
+        let name = row.name().text().to_string();
+
+        let mut push = |severity, error| report.issues.push(Issue { severity, error });
+
+        if rows[..idx]
+            .iter()
+            .any(|earlier| identity::same(earlier.name().text(), row.name().text()))
+        {
+            let severity = if duplicates == Duplicates::Ask {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+
+            push(
+                severity,
+                Error::DoubleGuesser {
+                    row: row.num(),
+                    name: name.clone(),
+                },
+            );
+        }
+
+        if !kept.contains(&row.num()) {
+            continue;
+        }
+
+        if guess.len() != board.squares() {
+            let severity = if guess.len() < board.squares() {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+
+            push(
+                severity,
+                Error::NotEnoughValidSquares {
+                    name: name.clone(),
+                    row: row.num(),
+                    amount: guess.len(),
+                    needed: board.squares(),
+                },
+            );
+        }
+
+        if let Some(limit) = board.max_passes()
+            && guess.passes() > limit
+        {
+            push(
+                Severity::Error,
+                Error::TooManyPasses {
+                    name: name.clone(),
+                    row: row.num(),
+                    amount: guess.passes(),
+                    limit,
+                },
+            );
+        }
+
+        if guess.wagers() > board.wagers().limit {
+            push(
+                Severity::Error,
+                Error::TooManyWagers {
+                    name,
+                    row: row.num(),
+                    amount: guess.wagers(),
+                    limit: board.wagers().limit,
+                },
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_every_issue() {
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]).with_max_passes(1);
+
+        let rows = [
+            Row::test(1, "Rolo", "PP YN", 0),
+            Row::test(2, "Edits", "YN", 0),
+            Row::test(3, "Rolo", "YNYN", 0),
+            Row::test(4, "Bam", "PPP Y", 0),
+            Row::test(5, "Khun", "YNYNY", 0),
+        ];
+
+        let issues = |report: &ValidationReport| -> Vec<(Severity, u32)> {
+            report
+                .issues
+                .iter()
+                .map(|issue| (issue.severity, issue.error.row().unwrap()))
+                .collect()
+        };
+
+        // Keeping the last guess leaves out the first, so its passes don't matter.
+        assert_eq!(
+            vec![
+                (Severity::Error, 2),
+                (Severity::Warning, 3),
+                (Severity::Error, 4),
+                (Severity::Warning, 5),
+            ],
+            issues(&validate(&board, &rows, Duplicates::KeepLast))
+        );
+
+        let report = validate(&board, &rows, Duplicates::Ask);
+
+        assert_eq!(
+            vec![
+                (Severity::Error, 1),
+                (Severity::Error, 2),
+                (Severity::Error, 3),
+                (Severity::Error, 4),
+                (Severity::Warning, 5),
+            ],
+            issues(&report)
+        );
+        assert_eq!(1, report.count(Severity::Warning));
+        assert!(matches!(
+            report.into_error(),
+            Some(Error::TooManyPasses { row: 1, .. })
+        ));
+    }
+}
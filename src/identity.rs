@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::error::AliasError;
+use crate::spreadsheet::{Name, Row};
+
+/// Letters from other scripts that look the same as a Latin one, which some names use to stand out.
+const CONFUSABLES: &[(char, char)] = &[
+    // Cyrillic
+    ('А', 'A'),
+    ('В', 'B'),
+    ('Е', 'E'),
+    ('К', 'K'),
+    ('М', 'M'),
+    ('Н', 'H'),
+    ('О', 'O'),
+    ('Р', 'P'),
+    ('С', 'C'),
+    ('Т', 'T'),
+    ('У', 'Y'),
+    ('Х', 'X'),
+    ('І', 'I'),
+    ('Ј', 'J'),
+    ('Ѕ', 'S'),
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('у', 'y'),
+    ('х', 'x'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('ԁ', 'd'),
+    ('ԛ', 'q'),
+    ('ԝ', 'w'),
+    // Greek
+    ('Α', 'A'),
+    ('Β', 'B'),
+    ('Ε', 'E'),
+    ('Ζ', 'Z'),
+    ('Η', 'H'),
+    ('Ι', 'I'),
+    ('Κ', 'K'),
+    ('Μ', 'M'),
+    ('Ν', 'N'),
+    ('Ο', 'O'),
+    ('Ρ', 'P'),
+    ('Τ', 'T'),
+    ('Υ', 'Y'),
+    ('Χ', 'X'),
+    ('ο', 'o'),
+    ('ν', 'v'),
+];
+
+/// The form names are compared in, so that the same person is recognised however their name was typed or pasted.
+///
+/// Whitespace is trimmed and runs of it become a single space, and invisible characters are dropped. Styled letters,
+/// like full width, bold or script ones, become their plain letter (NFKD), accents are dropped, look alike letters
+/// from other scripts become their Latin letter, and everything is lowercase.
+#[must_use]
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+
+    for word in name.split_whitespace() {
+        let mut letters = word
+            .nfkd()
+            .filter(|ch| !is_invisible(*ch) && !is_combining_mark(*ch))
+            .peekable();
+
+        if letters.peek().is_none() {
+            continue;
+        }
+
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+
+        for ch in letters {
+            normalized.extend(latin(ch).to_lowercase());
+        }
+    }
+
+    normalized
+}
+
+/// Whether two names belong to the same person, see [`normalize`].
+#[must_use]
+pub fn same(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Zero width characters, which Discord names sometimes carry along when copied.
+fn is_invisible(ch: char) -> bool {
+    matches!(ch, '\u{200b}'..='\u{200f}' | '\u{2060}' | '\u{feff}')
+}
+
+fn latin(ch: char) -> char {
+    CONFUSABLES
+        .iter()
+        .find(|(confusable, _)| *confusable == ch)
+        .map_or(ch, |(_, latin)| *latin)
+}
+
+/// Other names players have gone by, loaded from a `toml` file:
+///
+/// ```toml
+/// [[player]]
+/// name = "Rolo"
+/// aliases = ["RoloEdits", "rolo_old"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Aliases {
+    #[serde(rename = "player", default)]
+    players: Vec<AliasEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AliasEntry {
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+impl Aliases {
+    pub fn load(path: &Path) -> Result<Self, AliasError> {
+        let contents = fs::read_to_string(path).map_err(|err| AliasError::Io {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })?;
+
+        toml::from_str(&contents).map_err(|err| AliasError::Parse {
+            path: path.to_path_buf(),
+            reason: err.message().to_string(),
+        })
+    }
+
+    /// The name a player is listed under, if `name` is one of theirs.
+    #[must_use]
+    pub fn canonical(&self, name: &str) -> Option<&str> {
+        let name = normalize(name);
+
+        self.players
+            .iter()
+            .find(|entry| {
+                normalize(&entry.name) == name
+                    || entry.aliases.iter().any(|alias| normalize(alias) == name)
+            })
+            .map(|entry| entry.name.as_str())
+    }
+
+    /// Copies of the rows with every alias replaced by the name the player is listed under, keeping its colour.
+    #[must_use]
+    pub fn apply(&self, rows: &[Row]) -> Vec<Row> {
+        rows.iter()
+            .map(|row| match self.canonical(row.name().text()) {
+                Some(name) => Row {
+                    name: Name::new(name.to_string(), row.name().color()),
+                    ..row.clone()
+                },
+                None => row.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::Color32;
+
+    #[test]
+    fn should_normalize_names() {
+        for name in [
+            "Rolo",
+            " rolo ",
+            "ROLO",
+            "Rоlо",
+            "Ｒｏｌｏ",
+            "Ro\u{200b}lo",
+            "𝓡𝓸𝓵𝓸",
+            "𝐑𝐨𝐥𝐨",
+            "Rolò",
+            "Ro\u{300}lo",
+        ] {
+            assert_eq!("rolo", normalize(name), "{name}");
+        }
+
+        assert_eq!("edits & co", normalize("  Edits \t&  Co"));
+        assert!(!same("Rolo", "Roio"));
+    }
+
+    #[test]
+    fn should_apply_aliases() {
+        let aliases: Aliases = toml::from_str(
+            r#"
+[[player]]
+name = "Rolo"
+aliases = ["RoloEdits"]
+"#,
+        )
+        .unwrap();
+
+        let rows = aliases.apply(&[
+            Row {
+                num: 1,
+                name: Name::new(String::from("roloedits "), Color32::RED),
+                guess: String::from("YYNN"),
+                starting_score: 0,
+                submitted: None,
+            },
+            Row {
+                num: 2,
+                name: Name::new(String::from("Edits"), Color32::RED),
+                guess: String::from("NNNN"),
+                starting_score: 0,
+                submitted: None,
+            },
+        ]);
+
+        assert_eq!("Rolo", rows[0].name().text());
+        assert_eq!(Color32::RED, rows[0].name().color());
+        assert_eq!("Edits", rows[1].name().text());
+    }
+}
@@ -1,40 +1,71 @@
-use error::Error;
+use error::{Error, KeyError};
 use std::{convert::Infallible, fmt::Display, hash::Hash, path::Path, str::FromStr};
 
+pub mod discord;
 pub mod error;
-mod fonts;
+pub mod fonts;
 pub mod game;
+pub mod identity;
+pub mod notation;
+pub mod ods;
 pub mod spreadsheet;
 
-use game::{Game, great_war::GreatWar, normal::Normal};
+use game::{
+    Game, board::Board, custom::Custom, duplicates::Duplicates, great_war::GreatWar,
+    normal::Normal, score_card::ScoreCard, standings::Standings,
+};
 use spreadsheet::Row;
 
 #[derive(Debug)]
 pub enum Bingo {
     Normal(Normal),
     GreatWar(GreatWar),
+    Custom(Custom),
 }
 
 impl Bingo {
-    pub fn normal(rows: &[Row]) -> Result<Self, Error> {
-        Ok(Self::Normal(Normal::from_rows(rows)?))
+    pub fn normal(rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        Ok(Self::Normal(Normal::from_rows(rows, duplicates)?))
     }
 
-    pub fn great_war(rows: &[Row]) -> Result<Self, Error> {
-        Ok(Self::GreatWar(GreatWar::from_rows(rows)?))
+    pub fn great_war(rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        Ok(Self::GreatWar(GreatWar::from_rows(rows, duplicates)?))
+    }
+
+    pub fn custom(board: Board, rows: &[Row], duplicates: Duplicates) -> Result<Self, Error> {
+        Ok(Self::Custom(Custom::from_rows(board, rows, duplicates)?))
+    }
+
+    pub fn board(&self) -> &Board {
+        match self {
+            Self::Normal(normal) => normal.board(),
+            Self::GreatWar(great_war) => great_war.board(),
+            Self::Custom(custom) => custom.board(),
+        }
     }
 
     pub fn players(&self) -> &[Player] {
         match self {
             Self::Normal(normal) => normal.players(),
             Self::GreatWar(great_war) => great_war.players(),
+            Self::Custom(custom) => custom.players(),
         }
     }
 
-    pub fn play(&mut self, key: &Key) {
+    /// Fails without scoring when the key doesn't fit the board.
+    pub fn play(&mut self, key: &Key) -> Result<(), KeyError> {
         match self {
             Self::Normal(normal) => normal.play(key),
             Self::GreatWar(great_war) => great_war.play(key),
+            Self::Custom(custom) => custom.play(key),
+        }
+    }
+
+    pub fn rank(&mut self, standings: Standings) {
+        match self {
+            Self::Normal(normal) => normal.rank(standings),
+            Self::GreatWar(great_war) => great_war.rank(standings),
+            Self::Custom(custom) => custom.rank(standings),
         }
     }
 
@@ -42,26 +73,100 @@ impl Bingo {
         match self {
             Self::Normal(normal) => normal.save_png(path),
             Self::GreatWar(great_war) => great_war.save_png(path),
+            Self::Custom(custom) => custom.save_png(path),
+        }
+    }
+
+    pub fn save_csv(&self, path: &Path) -> std::io::Result<()> {
+        match self {
+            Self::Normal(normal) => normal.save_csv(path),
+            Self::GreatWar(great_war) => great_war.save_csv(path),
+            Self::Custom(custom) => custom.save_csv(path),
         }
     }
 }
 
+/// The answers to the board. Squares that haven't played out yet are marked with `?` and skipped when scoring, so
+/// the same guesses can be rescored as the story goes on. Squares that were thrown out are marked with `X`, and are
+/// worth nothing for everyone.
 #[derive(Debug)]
 pub struct Key(Vec<Square>);
 
-impl FromStr for Key {
-    type Err = Infallible;
+impl Key {
+    /// Number of squares that have an answer.
+    pub fn resolved(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|square| **square != Square::Unresolved)
+            .count()
+    }
 
-    fn from_str(key: &str) -> Result<Self, Self::Err> {
+    /// Indices of the squares that were thrown out.
+    pub fn voided(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, square)| **square == Square::Void)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Key {
+    /// Reads a key, along with every character that was ignored and its byte offset in the text. Whitespace and the
+    /// separators guesses allow, like `,` or `-`, are skipped without being reported.
+    pub fn parse(key: &str) -> (Self, Vec<(usize, char)>) {
         let mut result: Vec<Square> = Vec::with_capacity(12);
+        let mut dropped = Vec::new();
 
-        for square in key.chars() {
-            if matches!(square, 'Y' | 'y' | 'N' | 'n') {
+        for (idx, square) in key.char_indices() {
+            if matches!(square, 'Y' | 'y' | 'N' | 'n' | '?' | 'X' | 'x') {
                 result.push(Square::from_char(square));
+            } else if !notation::is_separator(square) {
+                dropped.push((idx, square));
             }
         }
 
-        Ok(Self(result))
+        (Self(result), dropped)
+    }
+}
+
+impl Key {
+    /// Checks that the key has an answer, `?` or `X` for every square of the board.
+    pub fn check(&self, board: &Board) -> Result<(), KeyError> {
+        if self.len() == board.squares() {
+            Ok(())
+        } else {
+            Err(KeyError::WrongLength {
+                amount: self.len(),
+                needed: board.squares(),
+            })
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = KeyError;
+
+    /// Fails on the first character that is neither a square nor a separator. [`Key::parse`] reads past them instead.
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let (parsed, dropped) = Self::parse(key);
+
+        match dropped.first() {
+            Some((offset, character)) => Err(KeyError::Unrecognised {
+                position: key[..*offset].chars().count() + 1,
+                character: *character,
+            }),
+            None => Ok(parsed),
+        }
     }
 }
 
@@ -76,55 +181,120 @@ impl<'a> IntoIterator for &'a Key {
 
 #[derive(PartialOrd, Ord, Debug, Clone)]
 pub struct Player {
+    /// Spreadsheet row the guess came from.
+    pub row: u32,
     pub name: String,
     pub color: String,
     pub guess: Guess,
+    /// Score carried in from previous weeks, which every scoring starts from.
+    pub starting_score: i32,
     pub score: i32,
+    /// Breakdown of the last scoring, already included in `score`.
+    pub card: ScoreCard,
+    /// Place in the standings before the last scoring, if the player had been scored before.
+    pub previous_place: Option<usize>,
+    /// Rank in the standings, starting from 1, or 0 when not yet scored.
+    pub rank: usize,
 }
 
+#[cfg(test)]
+impl Player {
+    /// A player that hasn't been scored, with `score` already filled in.
+    pub(crate) fn test(row: u32, name: &str, color: &str, guess: &str, score: i32) -> Self {
+        let Ok(guess) = Guess::from_str(guess);
+
+        Self {
+            row,
+            name: name.to_string(),
+            color: color.to_string(),
+            guess,
+            starting_score: 0,
+            score,
+            card: ScoreCard::default(),
+            previous_place: None,
+            rank: 0,
+        }
+    }
+}
+
+/// Players are the same person when their names are, see [`identity::normalize`].
 impl PartialEq for Player {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        identity::same(&self.name, &other.name)
     }
 }
 
 impl Eq for Player {}
 
+/// A player's picks. A `Y` or `N` followed by `!` is a wager, which multiplies the points of the square whether it
+/// hits or misses.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub struct Guess(Vec<Square>);
+pub struct Guess {
+    squares: Vec<Square>,
+    wagers: Vec<bool>,
+}
 
 impl Guess {
     fn iter(&self) -> std::slice::Iter<'_, Square> {
-        self.0.as_slice().iter()
+        self.squares.as_slice().iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.squares.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.squares.is_empty()
+    }
+
+    pub fn is_wagered(&self, square: usize) -> bool {
+        self.wagers.get(square).copied().unwrap_or_default()
+    }
+
+    /// Number of squares passed on.
+    pub fn passes(&self) -> usize {
+        self.squares
+            .iter()
+            .filter(|square| **square == Square::Pass)
+            .count()
+    }
+
+    /// Number of squares wagered on.
+    pub fn wagers(&self) -> usize {
+        self.wagers.iter().filter(|wager| **wager).count()
     }
 }
 
 impl Display for Guess {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for square in &self.0 {
+        for (square, wager) in self.squares.iter().zip(&self.wagers) {
             write!(f, "{square}")?;
+
+            if *wager {
+                write!(f, "!")?;
+            }
         }
 
         Ok(())
     }
 }
 
+impl Guess {
+    /// Reads a guess in any of the built in notations, along with every character that was ignored and its byte offset
+    /// in the text.
+    pub fn parse(s: &str) -> (Self, Vec<(usize, char)>) {
+        let parsed = notation::Notations::default().parse(s);
+
+        (parsed.guess, parsed.dropped)
+    }
+}
+
 impl FromStr for Guess {
     type Err = Infallible;
 
+    /// Reads a guess in any of the built in notations, see [`Guess::parse`] for what was ignored.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut guess: Vec<Square> = Vec::with_capacity(s.len());
-
-        for square in s.chars() {
-            if square.is_whitespace() {
-                continue;
-            }
-
-            if matches!(square, 'Y' | 'y' | 'N' | 'n' | 'P' | 'p') {
-                guess.push(Square::from_char(square));
-            }
-        }
-        Ok(Self(guess))
+        Ok(Self::parse(s).0)
     }
 }
 
@@ -133,13 +303,13 @@ impl<'a> IntoIterator for &'a Guess {
     type IntoIter = std::slice::Iter<'a, Square>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.squares.iter()
     }
 }
 
 impl Hash for Player {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+        identity::normalize(&self.name).hash(state);
     }
 }
 
@@ -148,6 +318,10 @@ pub enum Square {
     Yes,
     No,
     Pass,
+    /// Only used in a `Key`, for squares that have not been answered yet.
+    Unresolved,
+    /// Only used in a `Key`, for squares that were thrown out.
+    Void,
 }
 
 impl Square {
@@ -156,8 +330,12 @@ impl Square {
             'Y' | 'y' => Self::Yes,
             'N' | 'n' => Self::No,
             'P' | 'p' => Self::Pass,
+            '?' => Self::Unresolved,
+            'X' | 'x' => Self::Void,
             unknown => {
-                panic!("square guess was `{unknown}`, must be one of `Y`, `y`, `N`, `n`, `P`, `p`")
+                panic!(
+                    "square guess was `{unknown}`, must be one of `Y`, `y`, `N`, `n`, `P`, `p`, `?`, `X`, `x`"
+                )
             }
         }
     }
@@ -169,6 +347,8 @@ impl Display for Square {
             Self::Yes => "Y",
             Self::No => "N",
             Self::Pass => "P",
+            Self::Unresolved => "?",
+            Self::Void => "X",
         };
 
         write!(f, "{letter}")?;
@@ -176,3 +356,48 @@ impl Display for Square {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_dropped_characters() {
+        let (guess, dropped) = Guess::parse("YNB Y");
+
+        assert_eq!("YNY", guess.to_string());
+        assert_eq!(vec![(2, 'B')], dropped);
+
+        let (key, dropped) = Key::parse("YN? XB, Nä");
+
+        assert_eq!(5, key.len());
+        assert_eq!(vec![(5, 'B'), (9, 'ä')], dropped);
+    }
+
+    #[test]
+    fn should_validate_key() {
+        assert_eq!(
+            Some(KeyError::Unrecognised {
+                position: 4,
+                character: 'B'
+            }),
+            Key::from_str("YN?B").err()
+        );
+
+        let board = Board::new("Mini", &[&[5, 10], &[15, 20]]);
+
+        assert_eq!(Ok(()), Key::from_str("YN ?X").unwrap().check(&board));
+        assert_eq!(Ok(()), Key::from_str("Y,N-?|X").unwrap().check(&board));
+        assert_eq!(
+            Err(KeyError::WrongLength {
+                amount: 3,
+                needed: 4
+            }),
+            Key::from_str("YNY").unwrap().check(&board)
+        );
+
+        let mut bingo = Bingo::custom(board, &[], game::duplicates::Duplicates::default()).unwrap();
+
+        assert!(bingo.play(&Key::from_str("YNYNY").unwrap()).is_err());
+    }
+}
@@ -1,20 +1,38 @@
 // NOTE: Hide console in Windows when using release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use bingo::game::Game;
+use bingo::discord::Flagged;
+use bingo::error::{AliasError, BoardError, Error, NotationError, TeamError};
+use bingo::game::board::Board;
+use bingo::game::duplicates::{self, Duplicates};
 use bingo::game::great_war::GreatWar;
 use bingo::game::normal::Normal;
-use bingo::{Bingo, Key, spreadsheet::Row};
+use bingo::game::score_card::{Outcome, ScoreCard};
+use bingo::game::season::Season;
+use bingo::game::squares_list;
+use bingo::game::standings::{Ranking, Standings, TieBreak};
+use bingo::game::teams::{self, Team, TeamMap};
+use bingo::game::validation::{self, Severity, ValidationReport};
+use bingo::identity::{self, Aliases};
+use bingo::notation::{Mixed, Notations};
+use bingo::spreadsheet::{self, DEFAULT_COLOR, Layout, Layouts, Row, Week, Workbook};
+use bingo::{Bingo, Key, Player};
 use eframe::App;
 use eframe::egui::mutex::RwLock;
-use eframe::egui::{Align, Color32, Id, Layout, Modal, Ui};
+use eframe::egui::text::LayoutJob;
+use eframe::egui::{
+    Align, CollapsingHeader, Color32, ComboBox, DragValue, Grid, Id, Layout as UiLayout, Modal,
+    Stroke, TextFormat, TextStyle, Ui,
+};
 use egui_extras::{Column, TableBuilder};
 use mimalloc::MiMalloc;
+use std::cmp::Ordering;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::{ffi::OsStr, path::PathBuf};
-
-mod fonts;
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -31,9 +49,9 @@ fn main() {
         "Bingo",
         options,
         Box::new(|cc| {
-            cc.egui_ctx.set_fonts(fonts::load());
+            cc.egui_ctx.set_fonts(bingo::fonts::load());
             cc.egui_ctx.set_pixels_per_point(2.0);
-            Ok(Box::<Application>::default())
+            Ok(Box::new(Application::new()))
         }),
     )
     .unwrap();
@@ -61,24 +79,83 @@ struct Application {
     path: Arc<RwLock<Option<PathBuf>>>,
     key: String,
     bingo: Option<Bingo>,
+    /// Rules the current `bingo` was created with, so it is only reused when rescoring under the same rules.
+    scored_rules: Option<Rules>,
     scored: bool,
+    /// Every week of a workbook with a sheet per week, when scored as a season.
+    season: Option<Season>,
+    /// Standings shown for the season, a week by its index, or the totals.
+    season_view: Option<usize>,
+    /// Shown in a modal until dismissed.
+    error: Option<Error>,
     rules: Rules,
+    standings: Standings,
+    /// What to do with names that guessed more than once.
+    duplicates: Duplicates,
+    team_mode: TeamMode,
+    /// Picked in a background thread, then loaded into `team_map` on the next frame.
+    team_map_path: Arc<RwLock<Option<PathBuf>>>,
+    team_map: Option<TeamMap>,
+    team_error: Option<TeamError>,
     rows: Vec<Row>,
+    /// Messages left out when importing from Discord.
+    flagged: Vec<Flagged>,
+    /// Built in notations, and any custom ones from a `notations.toml` file next to the executable.
+    notations: Notations,
+    notation_error: Option<NotationError>,
+    /// Other names players have gone by, from an `aliases.toml` file next to the executable.
+    aliases: Aliases,
+    alias_error: Option<AliasError>,
+    /// Guesses that mix notations, shown so they can be checked.
+    mixed: Vec<Mixed>,
+    /// Problems with the guesses under the chosen rules.
+    report: ValidationReport,
+    /// Row picked in the diagnostics, highlighted in the preview.
+    highlighted: Option<u32>,
+    /// Scroll the preview to the highlighted row on the next frame.
+    scroll_to_highlighted: bool,
+    load_error: Option<String>,
+    /// The opened `xlsx` file, so that it can be read again when its layout is changed.
+    workbook: Option<Workbook>,
+    layout: Option<Layout>,
+    /// Write the key into the workbook when scoring.
+    save_key: bool,
+    /// Remembered between runs, in a `layouts.toml` file next to the executable.
+    layouts: Layouts,
+    layouts_path: Option<PathBuf>,
+    boards: Vec<Board>,
+    board_errors: Vec<BoardError>,
+    export_error: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Default, Clone, Copy)]
+enum TeamMode {
+    #[default]
+    Off,
+    /// Teams are the Discord role colours of the names.
+    ByColor,
+    /// Teams come from a mapping file.
+    FromFile,
 }
 
-#[derive(PartialEq, Eq, Default)]
+#[derive(PartialEq, Eq, Default, Clone, Copy)]
 enum Rules {
     #[default]
     Normal,
     GreatWar,
+    /// Index into the boards loaded at startup.
+    Custom(usize),
 }
 
 impl App for Application {
     #[allow(clippy::too_many_lines)]
     fn ui(&mut self, ui: &mut eframe::egui::Ui, _frame: &mut eframe::Frame) {
         eframe::egui::CentralPanel::default().show_inside(ui, |ui| {
-            if self.rows.is_empty() {
-                self.file_dialog( ui);
+            self.error_modal(ui);
+            self.load_error_modal(ui);
+
+            if self.rows.is_empty() && self.workbook.is_none() {
+                self.file_dialog(ui);
             } else {
                 ui.horizontal(|ui| {
                     let key_label = ui.label("Key: ");
@@ -89,68 +166,234 @@ impl App for Application {
                     // Formatting
                     self.key = self.key.to_uppercase().replace('\n', " ");
 
-                    let len = self.key.chars().filter(|ch| !ch.is_whitespace()).count();
-                    let enough = match self.rules {
-                        Rules::Normal => Normal::SQUARES == len,
-                        Rules::GreatWar => GreatWar::SQUARES == len,
-                    };
-
-                    if ui.add_enabled(enough, eframe::egui::Button::new("Score")).clicked() {
-                        let rows = self.rows.as_slice();
-                        let bingo = match self.rules {
-                            Rules::Normal => Bingo::normal(rows),
-                            Rules::GreatWar => Bingo::great_war(rows),
+                    let (parsed, _) = Key::parse(&self.key);
+                    let board = self.board();
+                    let squares = board.squares();
+
+                    let key = Key::from_str(&self.key)
+                        .and_then(|key| key.check(&board).map(|()| key));
+
+                    let mut score =
+                        ui.add_enabled(key.is_ok(), eframe::egui::Button::new("Score"));
+
+                    if let Err(err) = &key {
+                        score = score.on_disabled_hover_text(err.to_string());
+                    }
+
+                    if score.clicked()
+                        && let Ok(key) = &key
+                    {
+                        // Guesses in custom notations are rewritten in letters before scoring.
+                        let rows = self.notations.normalize(&self.rows);
+                        let rows = rows.as_slice();
+                        let bingo = match self.bingo.take() {
+                            // Rescoring the same submissions keeps track of how the standings moved.
+                            Some(bingo) if self.scored_rules == Some(self.rules) => Ok(bingo),
+                            _ => match self.rules {
+                                Rules::Normal => Bingo::normal(rows, self.duplicates),
+                                Rules::GreatWar => Bingo::great_war(rows, self.duplicates),
+                                Rules::Custom(idx) => Bingo::custom(self.boards[idx].clone(), rows, self.duplicates),
+                            },
                         };
 
-                        if let Err(err) = bingo {
-                            // FIX: Modals don't seem to open, though the closures are called.
-                            // For now, no errors will be returned and the onus is on GM to
-                            // account for correctness.
-                            match err {
-                                bingo::error::Error::DoubleGuesser { row, name } => {
-                                    Modal::new(Id::new("DG")).show(ui.ctx(), |ui| {
-                                        ui.set_width(200.0);
-                                        ui.heading("Player Guessed More Than Once");
-                                        ui.label(format!("{row}: {name}"));
-                                        ui.label("Fix the spreadsheet and save, then reload the file by pressing the Reload button, and rescore the bingo");
-                                    });
-                                }
-                                bingo::error::Error::NotEnoughValidSquares {
-                                    name,
-                                    row,
-                                    amount,
-                                    needed,
-                                } => {
-                                    Modal::new(Id::new("NES")).show(ui.ctx(), |ui| {
-                                        ui.set_width(200.0);
-                                        ui.heading("Incorrect Number of Squares");
-                                        ui.label(format!("{row}: {name} | Guessed for `{amount}` squares, needs `{needed}` squares "));
-                                        ui.label("Fix the spreadsheet and save, then reload the file by pressing the Reload button, and rescore the bingo");
-                                    });
+                        let bingo = bingo.and_then(|mut bingo| {
+                            bingo.play(key).map_err(Error::InvalidKey)?;
+                            Ok(bingo)
+                        });
+
+                        match bingo {
+                            Err(err) => {
+                                self.error = Some(err);
+                                self.scored = false;
+                            }
+                            Ok(mut bingo) => {
+                                bingo.rank(self.standings);
+                                self.bingo = Some(bingo);
+                                self.scored_rules = Some(self.rules);
+                                self.scored = true;
+                                self.season = None;
+
+                                if self.save_key
+                                    && let (Some(workbook), Some(layout)) =
+                                        (&mut self.workbook, &self.layout)
+                                {
+                                    self.export_error = workbook
+                                        .write_key(layout, &self.key)
+                                        .err()
+                                        .map(|err| format!("Failed to save the key: {err}"));
                                 }
                             }
-                        } else if let Ok(mut bingo) = bingo {
-                            let key = Key::from_str(&self.key).unwrap();
-                            bingo.play(&key);
-                            self.bingo = Some(bingo);
-                            self.scored = true;
                         }
                     }
+
+                    if self
+                        .workbook
+                        .as_ref()
+                        .is_some_and(Workbook::is_season)
+                        && ui
+                            .button("Score Season")
+                            .on_hover_text(
+                                "Scores every sheet as a week, in order, using the key in each \
+                                 sheet's Key row, and carries the totals forward by name",
+                            )
+                            .clicked()
+                    {
+                        self.score_season();
+                    }
+
+                    if self.workbook.is_some() {
+                        ui.checkbox(&mut self.save_key, "Save key to workbook")
+                            .on_hover_text(
+                                "Writes the key into the workbook when scoring, so it is filled \
+                                 in the next time the file is opened",
+                            );
+                    }
+
+                    if parsed.resolved() < parsed.len() {
+                        ui.weak(format!("{}/{squares} resolved", parsed.resolved()));
+                    }
                 });
 
+                let (_, dropped) = Key::parse(&self.key);
+
+                if !dropped.is_empty() {
+                    ui.colored_label(
+                        Color32::LIGHT_RED,
+                        format!("Not allowed in key: {}", dropped_list(&self.key, &dropped)),
+                    );
+                }
+
+                let rules = self.rules;
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.radio_value(&mut self.rules, Rules::Normal, Normal::NAME);
+                    ui.radio_value(&mut self.rules, Rules::GreatWar, GreatWar::NAME);
+
+                    for (idx, board) in self.boards.iter().enumerate() {
+                        ui.radio_value(&mut self.rules, Rules::Custom(idx), board.name());
+                    }
+                });
+
+                if rules != self.rules {
+                    let path = self.path.read().clone();
+
+                    // Which Discord messages count as a guess depends on the size of the board.
+                    match path {
+                        Some(path) if is_discord(&path) => self.load_error = self.load(&path).err(),
+                        _ => self.validate(),
+                    }
+                }
+
+                for err in &self.board_errors {
+                    ui.colored_label(Color32::LIGHT_RED, err.to_string());
+                }
+
+                if let Some(err) = &self.notation_error {
+                    ui.colored_label(Color32::LIGHT_RED, err.to_string());
+                }
+
+                if let Some(err) = &self.alias_error {
+                    ui.colored_label(Color32::LIGHT_RED, err.to_string());
+                }
+
+                let standings = self.standings;
+                let duplicates = self.duplicates;
+
                 ui.horizontal(|ui| {
-                    ui.radio_value(&mut self.rules, Rules::Normal, "Normal");
-                    ui.radio_value(&mut self.rules, Rules::GreatWar, "Great War");
+                    ComboBox::from_label("Ties")
+                        .selected_text(self.standings.tie_break.to_string())
+                        .show_ui(ui, |ui| {
+                            for tie_break in TieBreak::ALL {
+                                ui.selectable_value(
+                                    &mut self.standings.tie_break,
+                                    tie_break,
+                                    tie_break.to_string(),
+                                );
+                            }
+                        });
+
+                    ComboBox::from_label("Ranks")
+                        .selected_text(self.standings.ranking.to_string())
+                        .show_ui(ui, |ui| {
+                            for ranking in Ranking::ALL {
+                                ui.selectable_value(
+                                    &mut self.standings.ranking,
+                                    ranking,
+                                    ranking.to_string(),
+                                );
+                            }
+                        });
+
+                    ComboBox::from_label("Duplicates")
+                        .selected_text(self.duplicates.to_string())
+                        .show_ui(ui, |ui| {
+                            for policy in Duplicates::ALL {
+                                ui.selectable_value(
+                                    &mut self.duplicates,
+                                    policy,
+                                    policy.to_string(),
+                                );
+                            }
+                        });
                 });
 
+                if duplicates != self.duplicates {
+                    self.duplicates_changed();
+                }
+
+                if standings != self.standings {
+                    if let Some(bingo) = &mut self.bingo {
+                        bingo.rank(self.standings);
+                    }
+
+                    if let Some(season) = &mut self.season {
+                        season.rank(self.standings);
+                    }
+                }
+
+                self.team_options(ui);
+
                 if !self.scored {
                     ui.separator();
 
+                    let path = self.path.read().clone();
+
                     if ui.button("Reload File").clicked()
-                        && let Some(path) = self.path.read().as_ref() {
-                            self.rows = bingo::spreadsheet::read(path);
-                            self.scored = false;
-                        }
+                        && let Some(path) = path
+                    {
+                        self.load_error = self.load(&path).err();
+                    }
+
+                    self.layout_options(ui);
+
+                    if !self.flagged.is_empty() {
+                        CollapsingHeader::new(format!("Skipped Messages ({})", self.flagged.len()))
+                            .show(ui, |ui| flagged_table(ui, &self.flagged));
+                    }
+
+                    if !self.mixed.is_empty() {
+                        CollapsingHeader::new(format!("Mixed Notations ({})", self.mixed.len()))
+                            .default_open(true)
+                            .show(ui, |ui| mixed_table(ui, &self.mixed));
+                    }
+
+                    self.duplicates_picker(ui);
+
+                    if !self.report.is_empty() {
+                        let errors = self.report.count(Severity::Error);
+                        let warnings = self.report.count(Severity::Warning);
+
+                        CollapsingHeader::new(format!(
+                            "Diagnostics ({errors} errors, {warnings} warnings)"
+                        ))
+                        .default_open(errors > 0)
+                        .show(ui, |ui| {
+                            if let Some(row) = diagnostics_table(ui, &self.report) {
+                                self.highlighted = Some(row);
+                                self.scroll_to_highlighted = true;
+                            }
+                        });
+                    }
 
                     ui.separator();
                 }
@@ -162,68 +405,135 @@ impl App for Application {
 
                     ui.horizontal(|ui| {
                         if ui.button("Reload File").clicked() {
-                            self.rows = bingo::spreadsheet::read(self.path.read().as_ref().unwrap());
-                            self.scored = false;
+                            let path = self.path.read().clone().unwrap();
+                            self.load_error = self.load(&path).err();
                         }
 
                         if ui.button("Save").clicked() {
-                            self.bingo
-                                .as_ref()
-                                .unwrap()
-                                .save_png(self.path.read().as_ref().unwrap());
+                            let path = self.path.read();
+                            let path = path.as_ref().unwrap();
+
+                            match &self.season {
+                                Some(season) => season.save_png(path),
+                                None => self.bingo.as_ref().unwrap().save_png(path),
+                            }
+
+                            if let Some(teams) = self.teams() {
+                                teams::save_png(&teams, path);
+                            }
+                        }
+
+                        if ui.button("Export CSV").clicked() {
+                            let path = self.path.read();
+                            let path = path.as_ref().unwrap();
+
+                            let saved = match &self.season {
+                                Some(season) => season.save_csv(path),
+                                None => self.bingo.as_ref().unwrap().save_csv(path),
+                            };
+
+                            self.export_error = saved
+                                .err()
+                                .map(|err| format!("Failed to export CSV: {err}"));
                         }
                     });
 
-                    ui.separator();
+                    if let Some(err) = &self.export_error {
+                        ui.colored_label(Color32::LIGHT_RED, err);
+                    }
 
-                    let table = TableBuilder::new(ui)
-                        .striped(true)
-                        .cell_layout(Layout::left_to_right(
-                            Align::Center,
-                        ))
-                        .column(Column::exact(322.0))
-                        .column(Column::exact(36.0))
-                        .min_scrolled_height(0.0)
-                        .max_scroll_height(available_height);
+                    if let Some(season) = &self.season {
+                        ComboBox::from_label("Standings")
+                            .selected_text(
+                                self.season_view
+                                    .and_then(|week| season.weeks.get(week))
+                                    .map_or("Total", |week| week.name.as_str()),
+                            )
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.season_view, None, "Total");
 
-                    table
-                        .header(20.0, |mut header| {
-                            header.col(|ui| {
-                                ui.strong("Name");
-                            });
-                            header.col(|ui| {
-                                ui.strong("Score");
+                                for (idx, week) in season.weeks.iter().enumerate() {
+                                    ui.selectable_value(&mut self.season_view, Some(idx), &week.name);
+                                }
                             });
-                        })
-                        .body(|mut body| {
-                            for player in self.bingo.as_ref().unwrap().players() {
-                                body.row(18.0, |mut row| {
-                                    row.col(|ui| {
-                                        ui.colored_label(
-                                            Color32::from_hex(&player.color).expect(
-                                                "colors from spreadsheet should always be hex",
-                                            ),
-                                            player.name.clone(),
-                                        );
-                                    });
-                                    row.col(|ui| {
-                                        ui.label(player.score.to_string());
-                                    });
-                                });
+
+                        if let Some(teams) = self.teams() {
+                            CollapsingHeader::new("Teams")
+                                .default_open(true)
+                                .show(ui, |ui| team_table(ui, &teams));
+                        }
+
+                        ui.separator();
+
+                        let players = match self.season_view.and_then(|week| season.weeks.get(week)) {
+                            Some(week) => &week.standings,
+                            None => &season.total,
+                        };
+
+                        match &season.board {
+                            Some(board) => {
+                                standings_table(ui, board, players, ui.available_height());
                             }
-                        });
+                            None => {
+                                ui.label("No week has a key yet. Add a row named Key to each week's sheet.");
+                            }
+                        }
+
+                        return;
+                    }
+
+                    let bingo = self.bingo.as_ref().unwrap();
+
+                    let voided = bingo
+                        .players()
+                        .first()
+                        .map(|player| player.card.voided())
+                        .unwrap_or_default();
+
+                    if !voided.is_empty() {
+                        ui.colored_label(
+                            Color32::GOLD,
+                            format!(
+                                "Voided squares, worth nothing for everyone: {}",
+                                squares_list(&voided)
+                            ),
+                        );
+                    }
+
+                    if let Some(teams) = self.teams() {
+                        CollapsingHeader::new("Teams")
+                            .default_open(true)
+                            .show(ui, |ui| team_table(ui, &teams));
+                    }
+
+                    ui.separator();
+
+                    standings_table(ui, bingo.board(), bingo.players(), available_height);
                 } else {
-                    let table = TableBuilder::new(ui)
+                    let submitted = self.rows.iter().any(|row| row.submitted().is_some());
+
+                    let mut table = TableBuilder::new(ui)
                         .striped(true)
-                        .cell_layout(Layout::left_to_right(
-                            Align::Center,
-                        ))
+                        .cell_layout(UiLayout::left_to_right(Align::Center))
                         .column(Column::auto())
                         .column(Column::initial(200.0))
                         .column(Column::auto())
                         .min_scrolled_height(0.0)
                         .max_scroll_height(available_height);
 
+                    if submitted {
+                        table = table.column(Column::auto());
+                    }
+
+                    if std::mem::take(&mut self.scroll_to_highlighted)
+                        && let Some(idx) = self
+                            .rows
+                            .iter()
+                            .position(|row| Some(row.num()) == self.highlighted)
+                    {
+                        table = table.scroll_to_row(idx, Some(Align::Center));
+                    }
+
                     table
                         .header(20.0, |mut header| {
                             header.col(|ui| {
@@ -235,10 +545,17 @@ impl App for Application {
                             header.col(|ui| {
                                 ui.strong("Guess");
                             });
+                            if submitted {
+                                header.col(|ui| {
+                                    ui.strong("Submitted");
+                                });
+                            }
                         })
                         .body(|mut body| {
                             for row in &self.rows {
                                 body.row(18.0, |mut table_row| {
+                                    table_row.set_selected(Some(row.num()) == self.highlighted);
+
                                     table_row.col(|ui| {
                                         ui.label(row.num().to_string());
                                     });
@@ -246,8 +563,16 @@ impl App for Application {
                                         ui.colored_label(row.name().color(), row.name().text());
                                     });
                                     table_row.col(|ui| {
-                                        ui.label(row.guess());
+                                        guess_label(ui, row.guess(), &self.notations);
                                     });
+                                    if let Some(timestamp) = row.submitted() {
+                                        table_row.col(|ui| {
+                                            // `2024-03-01T16:00:00.000+00:00` down to the minute.
+                                            let minute = timestamp.get(..16).unwrap_or(timestamp);
+                                            ui.label(minute.replacen('T', " ", 1))
+                                                .on_hover_text(timestamp);
+                                        });
+                                    }
                                 });
                             }
                         });
@@ -257,7 +582,629 @@ impl App for Application {
     }
 }
 
+/// Heading and detail of a scoring error, for the error modal.
+fn describe(err: &Error) -> (&'static str, String) {
+    match err {
+        Error::DoubleGuesser { row, name } => {
+            ("Player Guessed More Than Once", format!("{row}: {name}"))
+        }
+        Error::NotEnoughValidSquares {
+            name,
+            row,
+            amount,
+            needed,
+        } => (
+            "Incorrect Number of Squares",
+            format!("{row}: {name} | Guessed for `{amount}` squares, needs `{needed}` squares"),
+        ),
+        Error::TooManyWagers {
+            name,
+            row,
+            amount,
+            limit,
+        } => (
+            "Too Many Wagers",
+            format!("{row}: {name} | Wagered on `{amount}` squares, the limit is `{limit}`"),
+        ),
+        Error::TooManyPasses {
+            name,
+            row,
+            amount,
+            limit,
+        } => (
+            "Too Many Passes",
+            format!("{row}: {name} | Passed on `{amount}` squares, the limit is `{limit}`"),
+        ),
+        Error::InvalidKey(err) => ("Invalid Key", err.to_string()),
+        Error::InWeek { week, error } => {
+            let (heading, detail) = describe(error);
+            (heading, format!("{week} | {detail}"))
+        }
+    }
+}
+
+/// A guess with the characters that are ignored when scoring highlighted.
+fn guess_label(ui: &mut Ui, guess: &str, notations: &Notations) {
+    let dropped = notations.parse(guess).dropped;
+
+    if dropped.is_empty() {
+        ui.label(guess);
+        return;
+    }
+
+    let normal = TextFormat {
+        font_id: TextStyle::Body.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..TextFormat::default()
+    };
+
+    let ignored = TextFormat {
+        color: Color32::LIGHT_RED,
+        underline: Stroke::new(1.0, Color32::LIGHT_RED),
+        ..normal.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    let mut idx = 0;
+
+    for (offset, ch) in &dropped {
+        let end = offset + ch.len_utf8();
+        job.append(&guess[idx..*offset], 0.0, normal.clone());
+        job.append(&guess[*offset..end], 0.0, ignored.clone());
+        idx = end;
+    }
+
+    job.append(&guess[idx..], 0.0, normal);
+
+    ui.label(job)
+        .on_hover_text(format!("Ignored: {}", dropped_list(guess, &dropped)));
+}
+
+/// Ignored characters, each with its one based position in the text.
+fn dropped_list(text: &str, dropped: &[(usize, char)]) -> String {
+    dropped
+        .iter()
+        .map(|(offset, ch)| format!("`{ch}` ({})", text[..*offset].chars().count() + 1))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether `path` is a Discord log or export, whose guesses are picked out by the size of the board.
+fn is_discord(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("txt" | "json")
+    )
+}
+
+/// Picks a column by its letters.
+fn column(column: &mut u32) -> DragValue<'_> {
+    DragValue::new(column)
+        .range(1..=u32::MAX)
+        .custom_formatter(|n, _| spreadsheet::column_name(n as u32))
+        .custom_parser(|text| spreadsheet::column_number(text).map(f64::from))
+}
+
+/// Players in order, with their rank, score and how far they moved since the last scoring.
+fn standings_table(ui: &mut Ui, board: &Board, players: &[Player], available_height: f32) {
+    let table = TableBuilder::new(ui)
+        .striped(true)
+        .cell_layout(UiLayout::left_to_right(Align::Center))
+        .column(Column::exact(24.0))
+        .column(Column::exact(292.0))
+        .column(Column::exact(36.0))
+        .column(Column::exact(30.0))
+        .min_scrolled_height(0.0)
+        .max_scroll_height(available_height);
+
+    table
+        .header(20.0, |mut header| {
+            header.col(|ui| {
+                ui.strong("#");
+            });
+            header.col(|ui| {
+                ui.strong("Name");
+            });
+            header.col(|ui| {
+                ui.strong("Score");
+            });
+            header.col(|_| {});
+        })
+        .body(|mut body| {
+            for (place, player) in players.iter().enumerate() {
+                body.row(18.0, |mut row| {
+                    row.col(|ui| {
+                        ui.label(player.rank.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.colored_label(
+                            Color32::from_hex(&player.color)
+                                .expect("colors from spreadsheet should always be hex"),
+                            player.name.clone(),
+                        );
+                    });
+                    row.col(|ui| {
+                        ui.label(player.score.to_string())
+                            .on_hover_ui(|ui| score_card(ui, board, &player.card));
+                    });
+                    row.col(|ui| {
+                        if let Some(previous) = player.previous_place {
+                            match previous.cmp(&place) {
+                                Ordering::Greater => ui.colored_label(
+                                    Color32::LIGHT_GREEN,
+                                    format!("▲{}", previous - place),
+                                ),
+                                Ordering::Less => ui.colored_label(
+                                    Color32::LIGHT_RED,
+                                    format!("▼{}", place - previous),
+                                ),
+                                Ordering::Equal => ui.weak("–"),
+                            };
+                        }
+                    });
+                });
+            }
+        });
+}
+
+/// Problems with the guesses, in row order. Returns the row that was clicked, if any.
+fn diagnostics_table(ui: &mut Ui, report: &ValidationReport) -> Option<u32> {
+    let mut clicked = None;
+
+    Grid::new("diagnostics").striped(true).show(ui, |ui| {
+        for issue in &report.issues {
+            let color = match issue.severity {
+                Severity::Error => Color32::LIGHT_RED,
+                Severity::Warning => Color32::GOLD,
+            };
+
+            let (heading, detail) = describe(&issue.error);
+
+            ui.colored_label(color, issue.severity.to_string());
+
+            // Every problem found by validation is with a guess, so has a row.
+            if let Some(row) = issue.error.row() {
+                if ui.link(format!("Row {row}")).clicked() {
+                    clicked = Some(row);
+                }
+            } else {
+                ui.label("");
+            }
+
+            ui.label(heading);
+            ui.weak(detail);
+            ui.end_row();
+        }
+    });
+
+    clicked
+}
+
+fn mixed_table(ui: &mut Ui, mixed: &[Mixed]) {
+    Grid::new("mixed").striped(true).show(ui, |ui| {
+        for guess in mixed {
+            ui.label(guess.row.to_string());
+            ui.label(&guess.name);
+            ui.label(
+                guess
+                    .notations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" + "),
+            );
+            if guess.crossed {
+                ui.weak("X is a no here, but voids the square in the key");
+            }
+            ui.end_row();
+        }
+    });
+}
+
+fn flagged_table(ui: &mut Ui, flagged: &[Flagged]) {
+    Grid::new("flagged").striped(true).show(ui, |ui| {
+        for message in flagged {
+            ui.label(message.line.to_string());
+            ui.label(&message.name);
+            ui.label(message.reason.to_string());
+            ui.weak(&message.message);
+            ui.end_row();
+        }
+    });
+}
+
+fn team_table(ui: &mut Ui, teams: &[Team]) {
+    Grid::new("teams").striped(true).show(ui, |ui| {
+        ui.strong("Team");
+        ui.strong("Players");
+        ui.strong("Total");
+        ui.strong("Average");
+        ui.end_row();
+
+        for team in teams {
+            ui.colored_label(
+                Color32::from_hex(&team.color).unwrap_or(Color32::LIGHT_GRAY),
+                &team.name,
+            );
+            ui.label(team.members.len().to_string())
+                .on_hover_text(team.members.join(", "));
+            ui.label(team.total.to_string());
+            ui.label(format!("{:.1}", team.average()));
+            ui.end_row();
+        }
+    });
+}
+
+/// Lays out the points of each square in the shape of the board, followed by any completed lines.
+fn score_card(ui: &mut Ui, board: &Board, card: &ScoreCard) {
+    let mut marks = card.marks.iter();
+
+    Grid::new("score_card").show(ui, |ui| {
+        for row in board.rows() {
+            for mark in marks.by_ref().take(row.len()) {
+                let color = match mark.outcome {
+                    Outcome::Hit => Color32::LIGHT_GREEN,
+                    Outcome::Miss => Color32::LIGHT_RED,
+                    Outcome::Pass | Outcome::Unresolved => Color32::GRAY,
+                    Outcome::Void => Color32::GOLD,
+                };
+
+                let wager = if mark.wagered { "!" } else { "" };
+                ui.colored_label(color, format!("{}{wager} {:+}", mark.guess, mark.points));
+            }
+
+            ui.end_row();
+        }
+    });
+
+    for bonus in &card.bonuses {
+        ui.label(format!("{}: {:+}", bonus.line, bonus.points));
+    }
+}
+
 impl Application {
+    /// Every name that guessed more than once, with its guesses side by side. Keeping one removes the others until the
+    /// file is reloaded.
+    fn duplicates_picker(&mut self, ui: &mut Ui) {
+        let groups = duplicates::groups(&self.rows);
+
+        if groups.is_empty() {
+            return;
+        }
+
+        let mut keep = None;
+
+        CollapsingHeader::new(format!("Duplicate Guesses ({})", groups.len()))
+            .default_open(self.duplicates == Duplicates::Ask)
+            .show(ui, |ui| {
+                for group in &groups {
+                    ui.horizontal_wrapped(|ui| {
+                        for row in group {
+                            ui.group(|ui| {
+                                ui.vertical(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.weak(format!("Row {}", row.num()));
+                                        ui.colored_label(row.name().color(), row.name().text());
+                                    });
+                                    guess_label(ui, row.guess(), &self.notations);
+
+                                    if let Some(timestamp) = row.submitted() {
+                                        ui.weak(timestamp);
+                                    }
+
+                                    if ui.button("Keep").clicked() {
+                                        keep = Some((row.name().text().to_string(), row.num()));
+                                    }
+                                });
+                            });
+                        }
+                    });
+                }
+            });
+
+        if let Some((name, num)) = keep {
+            self.rows
+                .retain(|row| !identity::same(row.name().text(), &name) || row.num() == num);
+            self.mixed = self.notations.mixed(&self.rows);
+            self.bingo = None;
+            self.validate();
+        }
+    }
+
+    /// Board of the chosen rules.
+    fn board(&self) -> Board {
+        match self.rules {
+            Rules::Normal => Board::new(Normal::NAME, Normal::BOARD),
+            Rules::GreatWar => Board::new(GreatWar::NAME, GreatWar::BOARD),
+            Rules::Custom(idx) => self.boards[idx].clone(),
+        }
+    }
+
+    /// Different guesses count under the new policy, so the players have to be made again, and the standings shown no
+    /// longer hold until scored again.
+    fn duplicates_changed(&mut self) {
+        self.bingo = None;
+        self.scored = false;
+        self.season = None;
+        self.validate();
+    }
+
+    /// Checks the guesses against the chosen rules, the same way they are read when scoring.
+    fn validate(&mut self) {
+        let rows = self.notations.normalize(&self.rows);
+        self.report = validation::validate(&self.board(), &rows, self.duplicates);
+        self.highlighted = None;
+    }
+
+    /// Scores every sheet of the workbook as a week, carrying the totals from week to week.
+    fn score_season(&mut self) {
+        let (Some(workbook), Some(layout)) = (&self.workbook, &self.layout) else {
+            return;
+        };
+
+        let weeks = match workbook.weeks(layout) {
+            Ok(weeks) => weeks,
+            Err(err) => {
+                self.load_error = Some(err.to_string());
+                return;
+            }
+        };
+
+        // Guesses in custom notations are rewritten in letters before scoring.
+        let weeks: Vec<Week> = weeks
+            .into_iter()
+            .map(|week| Week {
+                rows: self.notations.normalize(&self.aliases.apply(&week.rows)),
+                ..week
+            })
+            .collect();
+
+        let season = Season::play(&weeks, self.standings, |rows| match self.rules {
+            Rules::Normal => Bingo::normal(rows, self.duplicates),
+            Rules::GreatWar => Bingo::great_war(rows, self.duplicates),
+            Rules::Custom(idx) => Bingo::custom(self.boards[idx].clone(), rows, self.duplicates),
+        });
+
+        match season {
+            Ok(season) => {
+                self.season = Some(season);
+                self.season_view = None;
+                self.scored = true;
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    /// Sheet and columns the guesses are read from, for `xlsx` files. Changes are remembered for the file.
+    fn layout_options(&mut self, ui: &mut Ui) {
+        let (Some(workbook), Some(layout)) = (&self.workbook, &mut self.layout) else {
+            return;
+        };
+
+        let previous = layout.clone();
+
+        ui.horizontal_wrapped(|ui| {
+            ComboBox::from_label("Sheet")
+                .selected_text(&layout.sheet)
+                .show_ui(ui, |ui| {
+                    for sheet in workbook.sheets() {
+                        ui.selectable_value(&mut layout.sheet, sheet.clone(), sheet);
+                    }
+                });
+
+            ui.label("Name");
+            ui.add(column(&mut layout.name));
+            ui.label("Guess");
+            ui.add(column(&mut layout.guess));
+
+            let mut has_score = layout.score.is_some();
+            ui.checkbox(&mut has_score, "Score");
+
+            match (has_score, layout.score) {
+                (true, None) => layout.score = Some(layout.guess + 1),
+                (false, Some(_)) => layout.score = None,
+                _ => {}
+            }
+
+            if let Some(score) = &mut layout.score {
+                ui.add(column(score));
+            }
+
+            ui.label("From row");
+            ui.add(DragValue::new(&mut layout.first_row).range(1..=u32::MAX));
+
+            // Names without a colour of their own, or that were pasted as black.
+            ui.label("Default colour");
+            let mut fallback = Color32::from_hex(&layout.fallback).unwrap_or(DEFAULT_COLOR);
+            if ui.color_edit_button_srgba(&mut fallback).changed() {
+                layout.fallback = fallback.to_hex();
+            }
+        });
+
+        if layout.sheet != previous.sheet {
+            *layout = Layout {
+                fallback: layout.fallback.clone(),
+                ..workbook.detect(Some(&layout.sheet))
+            };
+        }
+
+        if *layout == previous {
+            return;
+        }
+
+        self.bingo = None;
+
+        if let Some(key) = workbook.key(layout) {
+            self.key = key;
+        }
+
+        match workbook.read(layout) {
+            Ok(rows) => self.rows = self.aliases.apply(&rows),
+            Err(err) => {
+                self.rows.clear();
+                self.report = ValidationReport::default();
+                self.load_error = Some(err.to_string());
+                return;
+            }
+        }
+
+        self.mixed = self.notations.mixed(&self.rows);
+
+        let path = self.path.read().clone();
+
+        if let (Some(file), Some(layouts_path)) = (path, &self.layouts_path) {
+            self.layouts.insert(&file, layout.clone());
+            self.load_error = self
+                .layouts
+                .save(layouts_path)
+                .err()
+                .map(|err| format!("Failed to remember the layout: {err}"));
+        }
+
+        self.validate();
+    }
+
+    fn team_options(&mut self, ui: &mut Ui) {
+        if let Some(path) = self.team_map_path.write().take() {
+            match TeamMap::load(&path) {
+                Ok(map) => {
+                    self.team_map = Some(map);
+                    self.team_error = None;
+                }
+                Err(err) => self.team_error = Some(err),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Teams:");
+            ui.radio_value(&mut self.team_mode, TeamMode::Off, "Off");
+            ui.radio_value(&mut self.team_mode, TeamMode::ByColor, "By Colour");
+            ui.radio_value(&mut self.team_mode, TeamMode::FromFile, "From File");
+
+            if self.team_mode == TeamMode::FromFile && ui.button("Open Teams").clicked() {
+                let path = Arc::clone(&self.team_map_path);
+
+                std::thread::spawn(move || {
+                    if let Some(file) = rfd::FileDialog::new()
+                        .add_filter("Teams", &["toml"])
+                        .pick_file()
+                    {
+                        *path.write() = Some(file);
+                    }
+                });
+            }
+        });
+
+        if self.team_mode == TeamMode::FromFile
+            && let Some(err) = &self.team_error
+        {
+            ui.colored_label(Color32::LIGHT_RED, err.to_string());
+        }
+    }
+
+    /// Teams of the scored players, if team mode is on. A season is grouped by the totals.
+    fn teams(&self) -> Option<Vec<Team>> {
+        let players = match &self.season {
+            Some(season) => &season.total,
+            None => self.bingo.as_ref()?.players(),
+        };
+
+        match self.team_mode {
+            TeamMode::Off => None,
+            TeamMode::ByColor => Some(teams::by_color(players)),
+            TeamMode::FromFile => self.team_map.as_ref().map(|map| map.teams(players)),
+        }
+    }
+
+    fn error_modal(&mut self, ui: &mut Ui) {
+        let Some(err) = &self.error else {
+            return;
+        };
+
+        let (heading, detail) = describe(err);
+
+        let modal = Modal::new(Id::new("error")).show(ui.ctx(), |ui| {
+            ui.set_width(200.0);
+            ui.heading(heading);
+            ui.label(detail);
+            ui.label("Fix the spreadsheet and save, then reload the file by pressing the Reload button, and rescore the bingo");
+        });
+
+        if modal.should_close() {
+            self.error = None;
+        }
+    }
+
+    /// Problems reloading a file, or reading it with a changed layout.
+    fn load_error_modal(&mut self, ui: &mut Ui) {
+        let Some(err) = &self.load_error else {
+            return;
+        };
+
+        let modal = Modal::new(Id::new("load_error")).show(ui.ctx(), |ui| {
+            ui.set_width(300.0);
+            ui.heading("Failed to Load File");
+            ui.separator();
+            ui.label(err);
+        });
+
+        if modal.should_close() {
+            self.load_error = None;
+        }
+    }
+
+    fn new() -> Self {
+        // Custom boards live in a `boards` directory next to the executable.
+        let (boards, board_errors) = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("boards")))
+            .map(|dir| Board::load_dir(&dir))
+            .unwrap_or_default();
+
+        let layouts_path = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("layouts.toml")));
+
+        // Custom notations are optional, so a missing file is not an error.
+        let (notations, notation_error) = match std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("notations.toml")))
+            .filter(|path| path.exists())
+            .map(|path| Notations::load(&path))
+        {
+            Some(Ok(notations)) => (notations, None),
+            Some(Err(err)) => (Notations::default(), Some(err)),
+            None => (Notations::default(), None),
+        };
+
+        // Likewise for aliases.
+        let (aliases, alias_error) = match std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join("aliases.toml")))
+            .filter(|path| path.exists())
+            .map(|path| Aliases::load(&path))
+        {
+            Some(Ok(aliases)) => (aliases, None),
+            Some(Err(err)) => (Aliases::default(), Some(err)),
+            None => (Aliases::default(), None),
+        };
+
+        Self {
+            boards,
+            board_errors,
+            notations,
+            notation_error,
+            aliases,
+            alias_error,
+            layouts: layouts_path
+                .as_deref()
+                .map(Layouts::load)
+                .unwrap_or_default(),
+            layouts_path,
+            ..Default::default()
+        }
+    }
+
     fn file_dialog(&mut self, ui: &mut Ui) {
         ui.vertical_centered(|ui| {
             ui.centered_and_justified(|ui| {
@@ -267,6 +1214,10 @@ impl Application {
                     std::thread::spawn(move || {
                         if let Some(file) = rfd::FileDialog::new()
                             .add_filter("Excel Spreadsheet", &["xlsx"])
+                            .add_filter("OpenDocument Spreadsheet", &["ods"])
+                            .add_filter("Comma or Tab Separated", &["csv", "tsv"])
+                            .add_filter("Discord Log", &["txt"])
+                            .add_filter("DiscordChatExporter JSON", &["json"])
                             .pick_file()
                         {
                             *path.write() = Some(file);
@@ -276,21 +1227,142 @@ impl Application {
             })
         });
 
-        if let Some(path) = self.path.read().as_ref() {
-            if path.extension() == Some(OsStr::new("xlsx")) {
-                self.rows = bingo::spreadsheet::read(path);
-            } else {
-                let modal = Modal::new(Id::new("IF")).show(ui.ctx(), |ui| {
-                    ui.set_width(200.0);
-                    ui.heading("Invalid Filetype:");
-                    ui.separator();
-                    ui.label("Must be xslx");
-                });
+        let path = self.path.read().clone();
+
+        if let Some(path) = path
+            && let Err(err) = self.load(&path)
+        {
+            let modal = Modal::new(Id::new("IF")).show(ui.ctx(), |ui| {
+                ui.set_width(200.0);
+                ui.heading("Invalid File:");
+                ui.separator();
+                ui.label(err);
+            });
+
+            if modal.should_close() {
+                *self.path.write() = None;
+            }
+        }
+    }
+
+    /// Reads the guesses from a spreadsheet, or from Discord, either a copied log saved as a text file or an export.
+    fn load(&mut self, path: &Path) -> Result<(), String> {
+        self.bingo = None;
+        self.scored = false;
+        self.season = None;
+        self.flagged.clear();
+        self.mixed.clear();
+        self.workbook = None;
+        self.layout = None;
+
+        match path.extension().and_then(OsStr::to_str) {
+            Some("xlsx") => {
+                let workbook = Workbook::open(path).map_err(|err| err.to_string())?;
+
+                let remembered = self
+                    .layouts
+                    .get(path)
+                    .filter(|layout| workbook.sheets().contains(&layout.sheet))
+                    .and_then(|layout| workbook.read(layout).ok())
+                    .filter(|rows| !rows.is_empty());
+
+                if let Some(rows) = remembered {
+                    self.layout = self.layouts.get(path).cloned();
+                    self.rows = rows;
+                } else {
+                    let layout = workbook.detect(None);
+                    self.rows = workbook.read(&layout).map_err(|err| err.to_string())?;
+                    self.layout = Some(layout);
+                }
+
+                if let Some(key) = self.layout.as_ref().and_then(|layout| workbook.key(layout)) {
+                    self.key = key;
+                }
 
-                if modal.should_close() {
-                    *self.path.write() = None;
+                self.workbook = Some(workbook);
+            }
+            Some("ods") => {
+                self.rows = bingo::ods::read(path).map_err(|err| err.to_string())?;
+            }
+            Some(extension @ ("csv" | "tsv")) => {
+                let delimiter = if extension == "csv" { b',' } else { b'\t' };
+
+                self.rows = bingo::spreadsheet::read_delimited(path, delimiter)
+                    .map_err(|err| err.to_string())?;
+            }
+            Some("txt") => {
+                let import =
+                    bingo::discord::read_log(path, &self.notations, self.board().squares())
+                        .map_err(|err| format!("Failed to read Discord log: {err}"))?;
+
+                if import.rows.is_empty() {
+                    return Err(String::from("No guesses found in the Discord log"));
                 }
+
+                self.rows = import.rows;
+                self.flagged = import.flagged;
+            }
+            Some("json") => {
+                let import =
+                    bingo::discord::read_export(path, &self.notations, self.board().squares())
+                        .map_err(|err| format!("Failed to read Discord export: {err}"))?;
+
+                if import.rows.is_empty() {
+                    return Err(String::from("No guesses found in the Discord export"));
+                }
+
+                self.rows = import.rows;
+                self.flagged = import.flagged;
+            }
+            _ => {
+                return Err(String::from(
+                    "Must be xlsx, ods, csv, tsv, a Discord log saved as txt, or a Discord export as json",
+                ));
             }
         }
+
+        // Renamed players are listed under the name they are known by.
+        self.rows = self.aliases.apply(&self.rows);
+        self.mixed = self.notations.mixed(&self.rows);
+        self.validate();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_forget_scores_when_duplicates_change() {
+        let rows = bingo::spreadsheet::parse_delimited(
+            Path::new("guesses.csv"),
+            "Rolo,YYYY YYYY YYYY".as_bytes(),
+            b',',
+        )
+        .unwrap();
+
+        let mut bingo = Bingo::normal(&rows, Duplicates::KeepFirst).unwrap();
+        bingo
+            .play(&Key::from_str("YYYY YYYY YYYY").unwrap())
+            .unwrap();
+
+        let mut app = Application {
+            rows,
+            bingo: Some(bingo),
+            scored_rules: Some(Rules::Normal),
+            scored: true,
+            duplicates: Duplicates::KeepFirst,
+            ..Application::default()
+        };
+
+        app.duplicates = Duplicates::KeepLast;
+        app.duplicates_changed();
+
+        // Showing the standings without a bingo to show would panic.
+        assert!(!app.scored);
+        assert!(app.bingo.is_none());
+        assert!(app.season.is_none());
     }
 }
@@ -0,0 +1,513 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::NotationError;
+use crate::spreadsheet::Row;
+use crate::{Guess, Square};
+
+/// An alphabet that guesses are written in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// `Y`, `N` and `P`.
+    Letters,
+    /// `yes`, `no` and `pass`.
+    Words,
+    /// `1` and `0`.
+    Digits,
+    /// `O` and `X`. In the key, `X` is a thrown out square instead, so guesses using it are flagged.
+    Circles,
+    /// ✅ and ❌.
+    Checkmarks,
+    /// 🟩 and 🟥.
+    Squares,
+    /// A mapping set up for a server, by its name.
+    Custom(String),
+}
+
+impl Display for Notation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Letters => write!(f, "Y/N"),
+            Self::Words => write!(f, "yes/no"),
+            Self::Digits => write!(f, "1/0"),
+            Self::Circles => write!(f, "O/X"),
+            Self::Checkmarks => write!(f, "✅/❌"),
+            Self::Squares => write!(f, "🟩/🟥"),
+            Self::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Symbol {
+    text: String,
+    square: Square,
+    /// Symbols shared by several notations, like `P` or ⬜ for a pass, don't count towards any of them.
+    notation: Option<Notation>,
+}
+
+/// The notations guesses are recognised in: the built in ones, and any custom ones for the server.
+#[derive(Debug, Clone)]
+pub struct Notations {
+    /// Longest first, so that `no` is read as a word rather than `N` and `O`.
+    symbols: Vec<Symbol>,
+}
+
+/// Custom notations, loaded from a `toml` file:
+///
+/// ```toml
+/// [[notation]]
+/// name = "Tower"
+/// yes = ["🗼"]
+/// no = ["🕳️"]
+/// pass = ["🤷"]
+/// ```
+#[derive(Debug, Deserialize)]
+struct NotationFile {
+    #[serde(rename = "notation", default)]
+    notations: Vec<NotationEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NotationEntry {
+    name: String,
+    #[serde(default)]
+    yes: Vec<String>,
+    #[serde(default)]
+    no: Vec<String>,
+    #[serde(default)]
+    pass: Vec<String>,
+}
+
+/// Characters people put between squares, which are skipped.
+pub(crate) fn is_separator(ch: char) -> bool {
+    ch.is_whitespace() || matches!(ch, ',' | '|' | '/' | '-' | '.' | '\u{fe0f}' | '\u{200d}')
+}
+
+impl Default for Notations {
+    fn default() -> Self {
+        let builtin: [(&str, Square, Option<Notation>); 17] = [
+            ("Y", Square::Yes, Some(Notation::Letters)),
+            ("N", Square::No, Some(Notation::Letters)),
+            ("P", Square::Pass, None),
+            ("yes", Square::Yes, Some(Notation::Words)),
+            ("no", Square::No, Some(Notation::Words)),
+            ("pass", Square::Pass, Some(Notation::Words)),
+            ("1", Square::Yes, Some(Notation::Digits)),
+            ("0", Square::No, Some(Notation::Digits)),
+            ("O", Square::Yes, Some(Notation::Circles)),
+            ("X", Square::No, Some(Notation::Circles)),
+            ("✅", Square::Yes, Some(Notation::Checkmarks)),
+            ("✔", Square::Yes, Some(Notation::Checkmarks)),
+            ("❌", Square::No, Some(Notation::Checkmarks)),
+            ("✖", Square::No, Some(Notation::Checkmarks)),
+            ("🟩", Square::Yes, Some(Notation::Squares)),
+            ("🟥", Square::No, Some(Notation::Squares)),
+            ("⬜", Square::Pass, None),
+        ];
+
+        let mut notations = Self {
+            symbols: builtin
+                .into_iter()
+                .map(|(text, square, notation)| Symbol {
+                    text: text.to_string(),
+                    square,
+                    notation,
+                })
+                .collect(),
+        };
+
+        notations.sort();
+
+        notations
+    }
+}
+
+impl Notations {
+    /// The built in notations, along with the custom ones in `path`. Custom symbols take precedence over built in
+    /// ones.
+    pub fn load(path: &Path) -> Result<Self, NotationError> {
+        let contents = fs::read_to_string(path).map_err(|err| NotationError::Io {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })?;
+
+        let file: NotationFile = toml::from_str(&contents).map_err(|err| NotationError::Parse {
+            path: path.to_path_buf(),
+            reason: err.message().to_string(),
+        })?;
+
+        Self::with_custom(file).map_err(|reason| NotationError::Parse {
+            path: path.to_path_buf(),
+            reason,
+        })
+    }
+
+    fn with_custom(file: NotationFile) -> Result<Self, String> {
+        let mut notations = Self::default();
+
+        for entry in file.notations {
+            let squares = [
+                (entry.yes, Square::Yes),
+                (entry.no, Square::No),
+                (entry.pass, Square::Pass),
+            ];
+
+            for (symbols, square) in squares {
+                for text in symbols {
+                    let text: String = text.chars().filter(|ch| !is_separator(*ch)).collect();
+
+                    if text.is_empty() {
+                        return Err(format!("`{}` has an empty symbol", entry.name));
+                    }
+
+                    // Inserted in front, so they win over built in symbols of the same length.
+                    notations.symbols.insert(
+                        0,
+                        Symbol {
+                            text,
+                            square,
+                            notation: Some(Notation::Custom(entry.name.clone())),
+                        },
+                    );
+                }
+            }
+        }
+
+        notations.sort();
+
+        Ok(notations)
+    }
+
+    fn sort(&mut self) {
+        self.symbols
+            .sort_by_key(|symbol| std::cmp::Reverse(symbol.text.chars().count()));
+    }
+
+    /// Reads a guess written in any of the notations. A `!` after a yes or no is a wager.
+    ///
+    /// List numbering, like `1.`, `2)`, `#3` or `no.4`, is skipped along with separators. Anything else that isn't a
+    /// symbol is dropped, and kept in [`Parsed::dropped`].
+    #[must_use]
+    pub fn parse(&self, text: &str) -> Parsed {
+        let mut parsed = Parsed {
+            guess: Guess {
+                squares: Vec::new(),
+                wagers: Vec::new(),
+            },
+            notations: Vec::new(),
+            dropped: Vec::new(),
+            crossed: false,
+        };
+
+        let mut idx = 0;
+
+        while let Some(ch) = text[idx..].chars().next() {
+            if is_separator(ch) {
+                idx += ch.len_utf8();
+                continue;
+            }
+
+            if let Some(len) = numbering(text, idx) {
+                idx += len;
+                continue;
+            }
+
+            // Only a yes or no can be wagered on, and only once.
+            if ch == '!'
+                && matches!(parsed.guess.squares.last(), Some(Square::Yes | Square::No))
+                && let Some(wager) = parsed.guess.wagers.last_mut()
+                && !*wager
+            {
+                *wager = true;
+                idx += 1;
+                continue;
+            }
+
+            let Some(symbol) = self
+                .symbols
+                .iter()
+                .find(|symbol| matches(text, idx, symbol))
+            else {
+                parsed.dropped.push((idx, ch));
+                idx += ch.len_utf8();
+                continue;
+            };
+
+            parsed.guess.squares.push(symbol.square);
+            parsed.guess.wagers.push(false);
+            parsed.crossed |= symbol.notation == Some(Notation::Circles) && symbol.text == "X";
+
+            if let Some(notation) = &symbol.notation
+                && !parsed.notations.contains(notation)
+            {
+                parsed.notations.push(notation.clone());
+            }
+
+            idx += symbol.text.len();
+        }
+
+        parsed
+    }
+
+    /// Guesses that were written in more than one notation, or with an `X` for a no, which are usually a mistake.
+    #[must_use]
+    pub fn mixed(&self, rows: &[Row]) -> Vec<Mixed> {
+        rows.iter()
+            .filter_map(|row| {
+                let parsed = self.parse(row.guess());
+
+                (parsed.is_mixed() || parsed.crossed).then(|| Mixed {
+                    row: row.num(),
+                    name: row.name().text().to_string(),
+                    notations: parsed.notations,
+                    crossed: parsed.crossed,
+                })
+            })
+            .collect()
+    }
+
+    /// Copies of the rows with every guess rewritten in letters, so that guesses in custom notations can be scored.
+    #[must_use]
+    pub fn normalize(&self, rows: &[Row]) -> Vec<Row> {
+        rows.iter()
+            .map(|row| Row {
+                guess: self.parse(row.guess()).guess.to_string(),
+                ..row.clone()
+            })
+            .collect()
+    }
+}
+
+/// Whether `symbol` is at `idx` in `text`. Letters are matched regardless of case, except for the `O` circle, as a
+/// lowercase `o` is far more likely part of a word. Words only match when they aren't part of a longer word.
+fn matches(text: &str, idx: usize, symbol: &Symbol) -> bool {
+    let end = idx + symbol.text.len();
+
+    let Some(candidate) = text.get(idx..end) else {
+        return false;
+    };
+
+    let same = if symbol.notation == Some(Notation::Circles) {
+        candidate == symbol.text
+    } else {
+        candidate.eq_ignore_ascii_case(&symbol.text)
+    };
+
+    if !same {
+        return false;
+    }
+
+    let word = symbol.text.chars().count() > 1 && symbol.text.chars().all(char::is_alphabetic);
+
+    !word
+        || !(text[..idx]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphabetic)
+            || text[end..].chars().next().is_some_and(char::is_alphabetic))
+}
+
+/// Length of the list numbering at `idx`, if there is any: a number with a `#` or `no.` in front, or one followed by
+/// `.` or `)` and a space, like `1. YYNN`. Numbers in the middle of a word or number, like the `0` of `1.0.1`, are
+/// squares instead.
+fn numbering(text: &str, idx: usize) -> Option<usize> {
+    if text[..idx]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+
+    let rest = &text[idx..];
+
+    let prefix = if rest.starts_with('#') {
+        1
+    } else if rest
+        .get(..3)
+        .is_some_and(|no| no.eq_ignore_ascii_case("no."))
+    {
+        3
+    } else {
+        0
+    };
+
+    let digits = rest[prefix..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .count();
+
+    if digits == 0 {
+        return None;
+    }
+
+    let number = prefix + digits;
+
+    if prefix > 0 {
+        return Some(number);
+    }
+
+    let mut after = rest[number..].chars();
+
+    (matches!(after.next(), Some('.' | ')')) && after.next().is_some_and(char::is_whitespace))
+        .then_some(number + 1)
+}
+
+/// A guess read through [`Notations::parse`].
+#[derive(Debug, Clone)]
+pub struct Parsed {
+    pub guess: Guess,
+    /// Notations used, in the order they first appear.
+    pub notations: Vec<Notation>,
+    /// Characters that weren't recognised, with their byte offset in the text.
+    pub dropped: Vec<(usize, char)>,
+    /// Whether an `X` was read as a no, which in the key would void the square.
+    pub crossed: bool,
+}
+
+impl Parsed {
+    pub fn is_mixed(&self) -> bool {
+        self.notations.len() > 1
+    }
+}
+
+/// A guess that mixes notations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mixed {
+    pub row: u32,
+    pub name: String,
+    pub notations: Vec<Notation>,
+    /// Used `X` for a no.
+    pub crossed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::Name;
+    use eframe::egui::Color32;
+
+    #[test]
+    fn should_read_builtin_notations() {
+        let notations = Notations::default();
+
+        for text in [
+            "YNP Y",
+            "yes no pass YES",
+            "1 0 ⬜ 1",
+            "O X P O",
+            "✅❌⬜✅",
+            "✔️ ✖️ ⬜ ✔️",
+            "🟩🟥⬜🟩",
+        ] {
+            let parsed = notations.parse(text);
+
+            assert_eq!("YNPY", parsed.guess.to_string(), "{text}");
+            assert!(parsed.dropped.is_empty(), "{text}");
+            assert!(!parsed.is_mixed(), "{text}");
+        }
+    }
+
+    #[test]
+    fn should_report_mixed_notations() {
+        let parsed = Notations::default().parse("YN ✅❌ 10");
+
+        assert_eq!("YNYNYN", parsed.guess.to_string());
+        assert_eq!(
+            vec![Notation::Letters, Notation::Checkmarks, Notation::Digits],
+            parsed.notations
+        );
+    }
+
+    #[test]
+    fn should_flag_crosses() {
+        let notations = Notations::default();
+
+        let rows: Vec<Row> = ["OXO", "YNY"]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, guess)| Row {
+                num: idx as u32 + 1,
+                name: Name::new(String::from("Rolo"), Color32::WHITE),
+                guess: guess.to_string(),
+                starting_score: 0,
+                submitted: None,
+            })
+            .collect();
+
+        let mixed = notations.mixed(&rows);
+
+        assert_eq!(1, mixed.len());
+        assert_eq!(1, mixed[0].row);
+        assert!(mixed[0].crossed);
+        assert_eq!(vec![Notation::Circles], mixed[0].notations);
+    }
+
+    #[test]
+    fn should_only_read_whole_words() {
+        let notations = Notations::default();
+
+        for (text, guess) in [("YNNO", "YNNY"), ("YNYNNO", "YNYNNY")] {
+            assert_eq!(guess, notations.parse(text).guess.to_string(), "{text}");
+        }
+
+        let parsed = notations.parse("n o");
+
+        assert_eq!("N", parsed.guess.to_string());
+        assert_eq!(vec![(2, 'o')], parsed.dropped);
+    }
+
+    #[test]
+    fn should_skip_list_numbering() {
+        let notations = Notations::default();
+
+        for (text, guess) in [
+            ("no.1 YYNN", "YYNN"),
+            ("#2 NNYY", "NNYY"),
+            ("1. YYNN 2) NNYY", "YYNNNNYY"),
+            ("1.0.1.1", "YNYY"),
+            ("1 0 1.", "YNY"),
+        ] {
+            let parsed = notations.parse(text);
+
+            assert_eq!(guess, parsed.guess.to_string(), "{text}");
+            assert!(parsed.dropped.is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn should_keep_wagers_and_dropped() {
+        let parsed = Notations::default().parse("✅! no! P! ?");
+
+        assert_eq!("Y!N!P", parsed.guess.to_string());
+        assert_eq!(vec![(10, '!'), (12, '?')], parsed.dropped);
+    }
+
+    #[test]
+    fn should_read_custom_notations() {
+        let file: NotationFile = toml::from_str(
+            r#"
+[[notation]]
+name = "Tower"
+yes = ["🗼"]
+no = ["🕳️"]
+"#,
+        )
+        .unwrap();
+
+        let notations = Notations::with_custom(file).unwrap();
+
+        let parsed = notations.parse("🗼🕳️ ⬜");
+
+        assert_eq!("YNP", parsed.guess.to_string());
+        assert_eq!(
+            vec![Notation::Custom(String::from("Tower"))],
+            parsed.notations
+        );
+    }
+}
@@ -0,0 +1,535 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use eframe::egui::Color32;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::error::SpreadsheetError;
+use crate::spreadsheet::{DEFAULT_COLOR, Name, Row};
+
+/// Only the name, guess and starting score columns are read.
+const COLUMNS: usize = 3;
+
+/// Reads an OpenDocument spreadsheet, as saved by LibreOffice, with the same layout as the `xlsx` one. A header row
+/// above the guesses is skipped.
+///
+/// Names keep the colour of the first coloured part of their text, or else the font colour of their cell.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Row>, SpreadsheetError> {
+    let path = path.as_ref();
+
+    let open = |reason: String| SpreadsheetError::Open {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let file = std::fs::File::open(path).map_err(|err| open(err.to_string()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|err| open(err.to_string()))?;
+
+    let mut content = String::new();
+    archive
+        .by_name("content.xml")
+        .map_err(|err| open(err.to_string()))?
+        .read_to_string(&mut content)
+        .map_err(|err| open(err.to_string()))?;
+
+    parse_content(path, &content)
+}
+
+#[derive(Debug, Default)]
+struct Style {
+    parent: Option<String>,
+    color: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Cell {
+    text: String,
+    /// Value of numeric cells, which can differ from the displayed text.
+    value: Option<String>,
+    style: Option<String>,
+    /// Style of the first span of text inside the cell.
+    span: Option<String>,
+}
+
+/// A sheet as it was read, with the first starting score that isn't a whole number.
+#[derive(Debug, Default)]
+struct Sheet {
+    name: String,
+    rows: Vec<Row>,
+    /// Row number and reason.
+    invalid: Option<(u32, String)>,
+}
+
+/// Parses the `content.xml` of an OpenDocument spreadsheet, reading `Sheet1`, or the first sheet if there is none by
+/// that name. `path` is only used to name the file in errors.
+pub fn parse_content(path: &Path, content: &str) -> Result<Vec<Row>, SpreadsheetError> {
+    let mut sheets = parse_sheets(content).map_err(|err| SpreadsheetError::Open {
+        path: path.to_path_buf(),
+        reason: err.to_string(),
+    })?;
+
+    let Some(sheet) = sheets
+        .iter()
+        .position(|sheet| sheet.name == "Sheet1")
+        .or((!sheets.is_empty()).then_some(0))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let sheet = sheets.swap_remove(sheet);
+
+    match sheet.invalid {
+        Some((row, reason)) => Err(SpreadsheetError::Cell {
+            path: path.to_path_buf(),
+            sheet: sheet.name,
+            row,
+            column: 3,
+            reason,
+        }),
+        None => Ok(sheet.rows),
+    }
+}
+
+fn parse_sheets(content: &str) -> Result<Vec<Sheet>, quick_xml::Error> {
+    let mut reader = quick_xml::Reader::from_str(content);
+
+    let mut styles: HashMap<String, Style> = HashMap::new();
+    let mut style: Option<String> = None;
+
+    let mut sheets: Vec<Sheet> = Vec::new();
+    let mut columns: Vec<Option<String>> = Vec::new();
+    let mut row = 0;
+    let mut rows_repeated = 1;
+    let mut cells: Vec<Cell> = Vec::new();
+    let mut cell: Option<(Cell, usize)> = None;
+    // Depth of the `text:p` the reader is in, as only their text is shown in the cell.
+    let mut paragraphs = 0;
+    // Depth of the cell comment the reader is in, which is skipped along with everything in it.
+    let mut annotations = 0;
+
+    loop {
+        let event = reader.read_event()?;
+
+        if annotations > 0 {
+            match &event {
+                Event::Start(element) if element.name().as_ref() == b"office:annotation" => {
+                    annotations += 1;
+                }
+                Event::End(element) if element.name().as_ref() == b"office:annotation" => {
+                    annotations -= 1;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let empty = matches!(event, Event::Empty(_));
+
+                match element.name().as_ref() {
+                    b"style:style" => {
+                        let Some(name) = attribute(element, "style:name")? else {
+                            continue;
+                        };
+
+                        styles.insert(
+                            name.clone(),
+                            Style {
+                                parent: attribute(element, "style:parent-style-name")?,
+                                color: None,
+                            },
+                        );
+                        style = Some(name);
+                    }
+                    b"style:text-properties" => {
+                        if let Some(name) = &style
+                            && let Some(color) = attribute(element, "fo:color")?
+                            && let Some(style) = styles.get_mut(name)
+                        {
+                            style.color = Some(color);
+                        }
+                    }
+                    b"office:annotation" if !empty => annotations = 1,
+                    b"table:table" => {
+                        let name = attribute(element, "table:name")?.unwrap_or_default();
+                        sheets.push(Sheet {
+                            name,
+                            ..Sheet::default()
+                        });
+                        columns.clear();
+                        row = 0;
+                    }
+                    b"table:table-column" => {
+                        let repeated = repeated(element, "table:number-columns-repeated")?;
+                        let default = attribute(element, "table:default-cell-style-name")?;
+
+                        for _ in 0..repeated.min(COLUMNS.saturating_sub(columns.len())) {
+                            columns.push(default.clone());
+                        }
+                    }
+                    b"table:table-row" => {
+                        rows_repeated = repeated(element, "table:number-rows-repeated")? as u32;
+
+                        if empty {
+                            row += rows_repeated;
+                        } else {
+                            cells.clear();
+                        }
+                    }
+                    b"table:table-cell" | b"table:covered-table-cell" => {
+                        let repeated = repeated(element, "table:number-columns-repeated")?;
+
+                        let new = Cell {
+                            value: attribute(element, "office:value")?,
+                            style: attribute(element, "table:style-name")?,
+                            ..Cell::default()
+                        };
+
+                        if empty {
+                            push(&mut cells, &new, repeated);
+                        } else {
+                            cell = Some((new, repeated));
+                        }
+                    }
+                    b"text:p" => {
+                        if let Some((cell, _)) = &mut cell
+                            && !cell.text.is_empty()
+                        {
+                            cell.text.push('\n');
+                        }
+
+                        if !empty {
+                            paragraphs += 1;
+                        }
+                    }
+                    b"text:span" => {
+                        if let Some((cell, _)) = &mut cell
+                            && cell.span.is_none()
+                        {
+                            cell.span = attribute(element, "text:style-name")?;
+                        }
+                    }
+                    b"text:s" => {
+                        if let Some((cell, _)) = &mut cell {
+                            let spaces = repeated(element, "text:c")?;
+                            cell.text.extend(std::iter::repeat_n(' ', spaces));
+                        }
+                    }
+                    b"text:tab" => {
+                        if let Some((cell, _)) = &mut cell {
+                            cell.text.push('\t');
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                if paragraphs > 0
+                    && let Some((cell, _)) = &mut cell
+                {
+                    cell.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"style:style" => style = None,
+                b"text:p" => paragraphs -= 1,
+                b"table:table-cell" | b"table:covered-table-cell" => {
+                    if let Some((new, repeated)) = cell.take() {
+                        push(&mut cells, &new, repeated);
+                    }
+                }
+                b"table:table-row" => {
+                    if let Some(sheet) = sheets.last_mut()
+                        && sheet.invalid.is_none()
+                    {
+                        match to_row(row + 1, &cells, &columns, &styles, sheet.rows.is_empty()) {
+                            Ok(Some(new)) => sheet.rows.push(new),
+                            Ok(None) => {}
+                            Err(reason) => sheet.invalid = Some((row + 1, reason)),
+                        }
+                    }
+
+                    row += rows_repeated;
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(sheets)
+}
+
+/// Only the first of a run of repeated rows is read, as a name can only be used once. A header is skipped when it is
+/// the `first` row with a name. Fails with the reason when the starting score isn't a whole number.
+fn to_row(
+    num: u32,
+    cells: &[Cell],
+    columns: &[Option<String>],
+    styles: &HashMap<String, Style>,
+    first: bool,
+) -> Result<Option<Row>, String> {
+    let Some(name) = cells.first() else {
+        return Ok(None);
+    };
+
+    let text = name.text.trim();
+
+    if text.is_empty() || (first && is_header(text)) {
+        return Ok(None);
+    }
+
+    // Coloured text wins over the colour of the cell, as it does in `xlsx` rich text.
+    let color = name
+        .span
+        .as_ref()
+        .and_then(|span| color(styles, span))
+        .or_else(|| {
+            name.style
+                .as_ref()
+                .or(columns.first().and_then(Option::as_ref))
+                .and_then(|style| color(styles, style))
+        })
+        .unwrap_or(DEFAULT_COLOR);
+
+    let guess = cells
+        .get(1)
+        .map(|cell| cell.text.clone())
+        .unwrap_or_default();
+
+    let score = match cells.get(2) {
+        Some(cell) => {
+            let value = cell.value.as_deref().unwrap_or(&cell.text).trim();
+
+            if value.is_empty() {
+                0
+            } else {
+                value
+                    .parse()
+                    .map_err(|_| format!("starting score `{value}` is not a whole number"))?
+            }
+        }
+        None => 0,
+    };
+
+    Ok(Some(Row {
+        num,
+        name: Name::new(text.to_string(), color),
+        guess,
+        starting_score: score,
+        submitted: None,
+    }))
+}
+
+/// A header above the guesses, like `Name | Guess | Score`, recognised by its name column as in `xlsx` sheets.
+fn is_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+
+    name.starts_with("name") || name == "player"
+}
+
+/// Font colour of a style, following its parents. Black is treated as unset, the same as for `xlsx`.
+fn color(styles: &HashMap<String, Style>, name: &str) -> Option<Color32> {
+    let mut style = styles.get(name);
+
+    // Bounded, in case of a cycle in a malformed file.
+    for _ in 0..16 {
+        let current = style?;
+
+        if let Some(hex) = &current.color {
+            return Color32::from_hex(hex)
+                .ok()
+                .filter(|color| *color != Color32::BLACK);
+        }
+
+        style = current
+            .parent
+            .as_ref()
+            .and_then(|parent| styles.get(parent));
+    }
+
+    None
+}
+
+fn push(cells: &mut Vec<Cell>, cell: &Cell, repeated: usize) {
+    for _ in 0..repeated.min(COLUMNS.saturating_sub(cells.len())) {
+        cells.push(cell.clone());
+    }
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, quick_xml::Error> {
+    Ok(match element.try_get_attribute(name)? {
+        Some(attribute) => Some(attribute.unescape_value()?.into_owned()),
+        None => None,
+    })
+}
+
+fn repeated(element: &BytesStart, name: &str) -> Result<usize, quick_xml::Error> {
+    Ok(attribute(element, name)?
+        .and_then(|repeated| repeated.parse().ok())
+        .unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0">
+<office:automatic-styles>
+<style:style style:name="ce1" style:family="table-cell"><style:text-properties fo:color="#ed4245"/></style:style>
+<style:style style:name="ce2" style:family="table-cell" style:parent-style-name="ce1"/>
+<style:style style:name="ce3" style:family="table-cell"><style:text-properties fo:color="#000000"/></style:style>
+<style:style style:name="T1" style:family="text"><style:text-properties fo:color="#c0c0c0"/></style:style>
+</office:automatic-styles>
+<office:body>
+<office:spreadsheet>
+<table:table table:name="Sheet1">
+<table:table-column table:number-columns-repeated="1024"/>
+<table:table-row>
+<table:table-cell table:style-name="ce1" office:value-type="string"><text:p>Rolo</text:p></table:table-cell>
+<table:table-cell office:value-type="string"><text:p>YYNN<text:s text:c="2"/>YNYN</text:p></table:table-cell>
+<table:table-cell office:value-type="float" office:value="120"><text:p>120.00</text:p></table:table-cell>
+</table:table-row>
+<table:table-row table:number-rows-repeated="2"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+<table:table-row>
+<table:table-cell table:style-name="ce2"><text:p>Edits &amp; Co</text:p></table:table-cell>
+<table:table-cell><text:p>NNNN</text:p></table:table-cell>
+</table:table-row>
+<table:table-row>
+<table:table-cell table:style-name="ce3"><text:p><text:span text:style-name="T1">Bam</text:span></text:p></table:table-cell>
+<table:table-cell table:number-columns-repeated="2"><text:p>0</text:p></table:table-cell>
+</table:table-row>
+<table:table-row>
+<table:table-cell><text:p>Khun</text:p></table:table-cell>
+</table:table-row>
+</table:table>
+</office:spreadsheet>
+</office:body>
+</office:document-content>"##;
+
+    #[test]
+    fn should_read_rows() {
+        let rows = parse_content(Path::new("guesses.ods"), CONTENT).unwrap();
+
+        let rows: Vec<(u32, &str, &str, i32)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.num(),
+                    row.name().text(),
+                    row.guess(),
+                    row.starting_score(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                (1, "Rolo", "YYNN  YNYN", 120),
+                (4, "Edits & Co", "NNNN", 0),
+                (5, "Bam", "0", 0),
+                (6, "Khun", "", 0),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn should_read_font_colors() {
+        let rows = parse_content(Path::new("guesses.ods"), CONTENT).unwrap();
+
+        let colors: Vec<Color32> = rows.iter().map(|row| row.name().color()).collect();
+
+        assert_eq!(
+            vec![
+                Color32::from_rgb(0xed, 0x42, 0x45),
+                Color32::from_rgb(0xed, 0x42, 0x45),
+                Color32::from_rgb(0xc0, 0xc0, 0xc0),
+                DEFAULT_COLOR,
+            ],
+            colors
+        );
+    }
+
+    #[test]
+    fn should_skip_header() {
+        let content = CONTENT.replacen(
+            "<table:table-row>",
+            concat!(
+                "<table:table-row>",
+                "<table:table-cell><text:p>Name</text:p></table:table-cell>",
+                "<table:table-cell><text:p>Guess</text:p></table:table-cell>",
+                "<table:table-cell><text:p>Score</text:p></table:table-cell>",
+                "</table:table-row>\n<table:table-row>",
+            ),
+            1,
+        );
+
+        let rows = parse_content(Path::new("guesses.ods"), &content).unwrap();
+
+        assert_eq!(4, rows.len());
+        assert_eq!("Rolo", rows[0].name().text());
+        assert_eq!(2, rows[0].num());
+    }
+
+    #[test]
+    fn should_skip_comments() {
+        let content = CONTENT.replacen(
+            "<text:p>Rolo</text:p>",
+            concat!(
+                "<office:annotation><dc:date>2024-01-01</dc:date>",
+                "<text:p>late entry</text:p></office:annotation>",
+                "<text:p>Rolo</text:p>",
+            ),
+            1,
+        );
+
+        let rows = parse_content(Path::new("guesses.ods"), &content).unwrap();
+
+        assert_eq!("Rolo", rows[0].name().text());
+    }
+
+    #[test]
+    fn should_prefer_text_color() {
+        let content = CONTENT.replacen(
+            "<text:p>Rolo</text:p>",
+            r#"<text:p><text:span text:style-name="T1">Rolo</text:span></text:p>"#,
+            1,
+        );
+
+        let rows = parse_content(Path::new("guesses.ods"), &content).unwrap();
+
+        assert_eq!(Color32::from_rgb(0xc0, 0xc0, 0xc0), rows[0].name().color());
+    }
+
+    #[test]
+    fn should_report_invalid_score() {
+        for score in ["12.7", "ten"] {
+            let content = CONTENT.replacen(
+                r#"office:value-type="float" office:value="120"><text:p>120.00</text:p>"#,
+                &format!("><text:p>{score}</text:p>"),
+                1,
+            );
+
+            let err = parse_content(Path::new("guesses.ods"), &content).unwrap_err();
+
+            assert!(
+                matches!(
+                    err,
+                    SpreadsheetError::Cell {
+                        row: 1,
+                        column: 3,
+                        ..
+                    }
+                ),
+                "{score}"
+            );
+        }
+    }
+}
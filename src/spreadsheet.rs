@@ -1,15 +1,24 @@
 use eframe::egui::Color32;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+use crate::error::SpreadsheetError;
+
+/// Name colour used when the source has none, matching Discord's default text colour.
+pub const DEFAULT_COLOR: Color32 = Color32::from_rgb(0xf2, 0xf3, 0xf5);
+
+#[derive(Debug, Clone)]
 pub struct Row {
     pub(crate) num: u32,
     pub(crate) name: Name,
     pub(crate) guess: String,
     pub(crate) starting_score: i32,
+    /// When the guess was sent, for guesses imported from Discord exports.
+    pub(crate) submitted: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Name {
     text: String,
     color: Color32,
@@ -49,60 +58,1008 @@ impl Row {
     pub fn starting_score(&self) -> i32 {
         self.starting_score
     }
+
+    pub fn submitted(&self) -> Option<&str> {
+        self.submitted.as_deref()
+    }
 }
 
-pub fn read<P: AsRef<Path>>(path: P) -> Vec<Row> {
-    let mut contents = Vec::new();
+#[cfg(test)]
+impl Row {
+    /// A row with a white name that wasn't imported from Discord.
+    pub(crate) fn test(num: u32, name: &str, guess: &str, starting_score: i32) -> Self {
+        Self {
+            num,
+            name: Name::new(name.to_string(), Color32::WHITE),
+            guess: guess.to_string(),
+            starting_score,
+            submitted: None,
+        }
+    }
+}
+
+/// Reads the guesses of a workbook, from `Sheet1` or else the first sheet, using the columns of its header row.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Row>, SpreadsheetError> {
+    let workbook = Workbook::open(path)?;
+    let layout = workbook.detect(None);
+
+    workbook.read(&layout)
+}
+
+/// Where the guesses are in a workbook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layout {
+    pub sheet: String,
+    /// Columns are numbered from 1, for `A`.
+    pub name: u32,
+    pub guess: u32,
+    /// Starting scores are 0 when there is no column for them.
+    pub score: Option<u32>,
+    /// The first row after the header, or 1 when there is none.
+    pub first_row: u32,
+    /// Colour, in hex, of names without one of their own.
+    #[serde(default = "default_fallback")]
+    pub fallback: String,
+}
+
+fn default_fallback() -> String {
+    DEFAULT_COLOR.to_hex()
+}
+
+/// Name of the sheet, or of the row in the guesses sheet, that holds the answer key.
+const KEY: &str = "Key";
+
+fn is_key(name: &str) -> bool {
+    name.trim().eq_ignore_ascii_case(KEY)
+}
+
+/// A sheet of a workbook that keeps a sheet per week.
+#[derive(Debug, Clone)]
+pub struct Week {
+    /// Name of the sheet.
+    pub name: String,
+    pub rows: Vec<Row>,
+    /// The week's key, or `None` when it hasn't been played yet.
+    pub key: Option<String>,
+}
+
+/// How many rows are searched for a header.
+const HEADER_ROWS: u32 = 10;
+
+/// An `xlsx` workbook, kept open so that it can be read again with a different layout.
+pub struct Workbook {
+    path: PathBuf,
+    spreadsheet: umya_spreadsheet::Spreadsheet,
+}
+
+impl Workbook {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SpreadsheetError> {
+        let path = path.as_ref();
+
+        let spreadsheet =
+            umya_spreadsheet::reader::xlsx::read(path).map_err(|err| SpreadsheetError::Open {
+                path: path.to_path_buf(),
+                reason: err.to_string(),
+            })?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            spreadsheet,
+        })
+    }
+
+    pub fn sheets(&self) -> Vec<String> {
+        self.spreadsheet
+            .get_sheet_collection()
+            .iter()
+            .map(|sheet| sheet.get_name().to_string())
+            .collect()
+    }
+
+    /// Whether the workbook keeps a sheet per week, having more than one sheet besides `Key`.
+    pub fn is_season(&self) -> bool {
+        self.sheets().iter().filter(|sheet| !is_key(sheet)).count() > 1
+    }
 
-    let workbook = umya_spreadsheet::reader::xlsx::read(path).expect("failed to read xlsx file");
+    /// Finds the `Name`, `Guess` and `Score` header of a sheet, falling back to columns `A`, `B` and `C` from the top
+    /// when there is none.
+    ///
+    /// Without a sheet, `Sheet1` is used, or the first sheet if none is named that.
+    pub fn detect(&self, sheet: Option<&str>) -> Layout {
+        let sheets = self.sheets();
 
-    let worksheet = workbook
-        .get_sheet_by_name("Sheet1")
-        .expect("failed to read worksheet");
+        let sheet = sheet
+            .filter(|sheet| sheets.iter().any(|name| name == sheet))
+            .or_else(|| {
+                sheets
+                    .iter()
+                    .find(|name| *name == "Sheet1")
+                    .map(String::as_str)
+            })
+            .or_else(|| sheets.iter().find(|name| !is_key(name)).map(String::as_str))
+            .unwrap_or("Sheet1")
+            .to_string();
 
-    for row in 1.. {
-        let Some(cell) = worksheet.get_cell((1, row)) else {
-            break;
+        let mut layout = Layout {
+            sheet,
+            name: 1,
+            guess: 2,
+            score: Some(3),
+            first_row: 1,
+            fallback: default_fallback(),
         };
 
-        let text = cell.get_cell_value().get_value();
+        let Some(worksheet) = self.spreadsheet.get_sheet_by_name(&layout.sheet) else {
+            return layout;
+        };
 
-        if text.is_empty() {
-            continue;
+        for row in 1..=HEADER_ROWS {
+            let mut name = None;
+            let mut guess = None;
+            let mut score = None;
+
+            for column in 1..=worksheet.get_highest_column() {
+                let header = worksheet.get_value((column, row)).trim().to_lowercase();
+
+                if header.starts_with("name") || header == "player" {
+                    name.get_or_insert(column);
+                } else if header.starts_with("guess") {
+                    guess.get_or_insert(column);
+                } else if header.contains("score") {
+                    score.get_or_insert(column);
+                }
+            }
+
+            if let (Some(name), Some(guess)) = (name, guess) {
+                layout.name = name;
+                layout.guess = guess;
+                layout.score = score;
+                layout.first_row = row + 1;
+                break;
+            }
         }
 
-        let hex = cell
+        layout
+    }
+
+    /// Reads every row with a name, from `first_row` on, except for a key row. Starting scores must be whole numbers,
+    /// or empty for 0.
+    pub fn read(&self, layout: &Layout) -> Result<Vec<Row>, SpreadsheetError> {
+        let mut contents = Vec::new();
+
+        let worksheet = self
+            .spreadsheet
+            .get_sheet_by_name(&layout.sheet)
+            .ok_or_else(|| SpreadsheetError::MissingSheet {
+                path: self.path.clone(),
+                sheet: layout.sheet.clone(),
+            })?;
+
+        for row in layout.first_row..=worksheet.get_highest_row() {
+            let Some(cell) = worksheet.get_cell((layout.name, row)) else {
+                continue;
+            };
+
+            let text = cell.get_cell_value().get_value();
+
+            if text.is_empty() || is_key(&text) {
+                continue;
+            }
+
+            let color = self
+                .name_color(cell)
+                .unwrap_or_else(|| Color32::from_hex(&layout.fallback).unwrap_or(DEFAULT_COLOR));
+
+            let guess = worksheet.get_value((layout.guess, row));
+            let score = match layout.score {
+                Some(column) => {
+                    let value = worksheet.get_value((column, row));
+                    let value = value.trim();
+
+                    if value.is_empty() {
+                        0
+                    } else {
+                        value.parse().map_err(|_| SpreadsheetError::Cell {
+                            path: self.path.clone(),
+                            sheet: layout.sheet.clone(),
+                            row,
+                            column,
+                            reason: format!("starting score `{value}` is not a whole number"),
+                        })?
+                    }
+                }
+                None => 0,
+            };
+
+            contents.push(Row {
+                num: row,
+                name: Name::new(text.to_string(), color),
+                guess,
+                starting_score: score,
+                submitted: None,
+            });
+        }
+
+        Ok(contents)
+    }
+}
+
+impl Workbook {
+    /// The answer key stored in the workbook, to be read with [`Key::from_str`](std::str::FromStr).
+    ///
+    /// A row named `Key` in the guesses sheet holds the key in its guess column. Otherwise, a sheet named `Key` holds
+    /// it in `A1`.
+    pub fn key(&self, layout: &Layout) -> Option<String> {
+        let from_row = self
+            .spreadsheet
+            .get_sheet_by_name(&layout.sheet)
+            .and_then(|worksheet| {
+                let row = self.key_row(layout)?;
+                Some(worksheet.get_value((layout.guess, row)))
+            });
+
+        let from_sheet = || {
+            let sheet = self.sheets().into_iter().find(|name| is_key(name))?;
+            Some(self.spreadsheet.get_sheet_by_name(&sheet)?.get_value("A1"))
+        };
+
+        from_row
+            .or_else(from_sheet)
+            .filter(|key| !key.trim().is_empty())
+    }
+
+    /// Saves the key into the workbook, where [`Workbook::key`] reads it from, and writes the workbook back to its
+    /// file. A `Key` sheet is added when there is nowhere to put it yet.
+    ///
+    /// The file is read again first, so that anything changed in it since it was opened is kept rather than
+    /// overwritten, and the workbook is replaced with what was saved.
+    pub fn write_key(&mut self, layout: &Layout, key: &str) -> Result<(), SpreadsheetError> {
+        let mut current = Self::open(&self.path)?;
+        current.set_key(layout, key)?;
+
+        umya_spreadsheet::writer::xlsx::write(&current.spreadsheet, &current.path).map_err(
+            |err| SpreadsheetError::Write {
+                path: current.path.clone(),
+                reason: err.to_string(),
+            },
+        )?;
+
+        *self = current;
+
+        Ok(())
+    }
+
+    fn set_key(&mut self, layout: &Layout, key: &str) -> Result<(), SpreadsheetError> {
+        let row = self.key_row(layout);
+        let sheet = self.sheets().into_iter().find(|name| is_key(name));
+
+        let cell = match (row, sheet) {
+            (Some(row), _) => self
+                .spreadsheet
+                .get_sheet_by_name_mut(&layout.sheet)
+                .map(|worksheet| worksheet.get_cell_mut((layout.guess, row))),
+            (None, Some(sheet)) => self
+                .spreadsheet
+                .get_sheet_by_name_mut(&sheet)
+                .map(|worksheet| worksheet.get_cell_mut("A1")),
+            (None, None) => self
+                .spreadsheet
+                .new_sheet(KEY)
+                .ok()
+                .map(|worksheet| worksheet.get_cell_mut("A1")),
+        };
+
+        cell.ok_or_else(|| SpreadsheetError::MissingSheet {
+            path: self.path.clone(),
+            sheet: layout.sheet.clone(),
+        })?
+        .set_value(key);
+
+        Ok(())
+    }
+
+    /// Every sheet other than `Key` as a week, in the order of the workbook, for workbooks that keep a sheet per week.
+    ///
+    /// Each sheet's header is found on its own, and its key is read from its own `Key` row, as the `Key` sheet only
+    /// holds a single key. Names without a colour take the fallback of `layout`.
+    pub fn weeks(&self, layout: &Layout) -> Result<Vec<Week>, SpreadsheetError> {
+        self.sheets()
+            .into_iter()
+            .filter(|sheet| !is_key(sheet))
+            .map(|sheet| {
+                let week = Layout {
+                    fallback: layout.fallback.clone(),
+                    ..self.detect(Some(&sheet))
+                };
+
+                let key = self
+                    .spreadsheet
+                    .get_sheet_by_name(&sheet)
+                    .zip(self.key_row(&week))
+                    .map(|(worksheet, row)| worksheet.get_value((week.guess, row)))
+                    .filter(|key| !key.trim().is_empty());
+
+                Ok(Week {
+                    rows: self.read(&week)?,
+                    name: sheet,
+                    key,
+                })
+            })
+            .collect()
+    }
+
+    fn key_row(&self, layout: &Layout) -> Option<u32> {
+        let worksheet = self.spreadsheet.get_sheet_by_name(&layout.sheet)?;
+
+        (layout.first_row..=worksheet.get_highest_row())
+            .find(|row| is_key(&worksheet.get_value((layout.name, *row))))
+    }
+
+    /// Colour a name is shown in on Discord. When only parts of the name were coloured, the colour covering most of it
+    /// is used, with uncoloured parts taking the colour of the cell.
+    fn name_color(&self, cell: &umya_spreadsheet::Cell) -> Option<Color32> {
+        let theme = self.spreadsheet.get_theme();
+
+        // Cells that were never styled have no font.
+        let cell_color = cell
             .get_style()
             .get_font()
-            .unwrap()
-            .get_color()
-            .get_argb_with_theme(workbook.get_theme());
-
-        let color = if hex.len() == 6 {
-            if hex == "000000" {
-                String::from("#f2f3f5")
-            } else {
-                format!("#{hex}")
+            .and_then(|font| font_color(font.get_color(), theme));
+
+        let umya_spreadsheet::CellRawValue::RichText(rich_text) = cell.get_raw_value() else {
+            return cell_color;
+        };
+
+        let mut colors: Vec<(Option<Color32>, usize)> = Vec::new();
+
+        for run in rich_text.get_rich_text_elements() {
+            let len = run
+                .get_text()
+                .chars()
+                .filter(|ch| !ch.is_whitespace())
+                .count();
+
+            if len == 0 {
+                continue;
             }
-        } else if let Some(hex) = hex.get(2..) {
-            format!("#{hex}")
+
+            let color = run
+                .get_run_properties()
+                .and_then(|font| font_color(font.get_color(), theme))
+                .or(cell_color);
+
+            match colors.iter_mut().find(|(existing, _)| *existing == color) {
+                Some((_, total)) => *total += len,
+                None => colors.push((color, len)),
+            }
+        }
+
+        colors
+            .iter()
+            .rev()
+            .max_by_key(|(_, len)| *len)
+            .map_or(cell_color, |(color, _)| *color)
+    }
+}
+
+/// Resolves theme colours and tints. Black counts as no colour, as that is what Discord's default text pastes as.
+fn font_color(
+    color: &umya_spreadsheet::Color,
+    theme: &umya_spreadsheet::drawing::Theme,
+) -> Option<Color32> {
+    let argb = color.get_argb();
+
+    let rgb = if argb.is_empty() {
+        // Theme colours have no argb of their own, and colours that were never set have neither.
+        if color.get_argb_with_theme(theme).is_empty() {
+            return None;
+        }
+
+        theme
+            .get_theme_elements()
+            .get_color_scheme()
+            .get_color_map()
+            .get(*color.get_theme_index() as usize)?
+            .clone()
+    } else {
+        argb.get(argb.len().saturating_sub(6)..)?.to_string()
+    };
+
+    let color = tint(
+        Color32::from_hex(&format!("#{rgb}")).ok()?,
+        *color.get_tint(),
+    );
+
+    (color != Color32::BLACK).then_some(color)
+}
+
+/// Lightens a colour for a positive tint, or darkens it for a negative one, the way Office does.
+fn tint(color: Color32, tint: f64) -> Color32 {
+    if tint == 0.0 {
+        return color;
+    }
+
+    let [r, g, b, _] = color.to_array().map(|channel| f64::from(channel) / 255.0);
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let mut lightness = (max + min) / 2.0;
+
+    let (hue, saturation) = if max == min {
+        (0.0, 0.0)
+    } else {
+        let delta = max - min;
+
+        let saturation = if lightness > 0.5 {
+            delta / (2.0 - max - min)
+        } else {
+            delta / (max + min)
+        };
+
+        let hue = if max == r {
+            (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / delta + 2.0
         } else {
-            String::from("#f2f3f5")
+            (r - g) / delta + 4.0
         };
 
-        let color =
-            Color32::from_hex(&color).expect("colors from spreadsheet should always be hex");
+        (hue / 6.0, saturation)
+    };
+
+    lightness = if tint < 0.0 {
+        lightness * (1.0 + tint)
+    } else {
+        lightness * (1.0 - tint) + tint
+    };
+
+    let q = if lightness < 0.5 {
+        lightness * (1.0 + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2.0 * lightness - q;
+
+    let channel = |t: f64| {
+        let t = t.rem_euclid(1.0);
+
+        let value = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+
+        (value * 255.0).round() as u8
+    };
+
+    Color32::from_rgb(
+        channel(hue + 1.0 / 3.0),
+        channel(hue),
+        channel(hue - 1.0 / 3.0),
+    )
+}
+
+/// Column letters, like `A` or `AB`, for columns numbered from 1.
+pub fn column_name(mut column: u32) -> String {
+    let mut name = Vec::new();
+
+    while column > 0 {
+        column -= 1;
+        name.push(char::from(b'A' + (column % 26) as u8));
+        column /= 26;
+    }
+
+    name.iter().rev().collect()
+}
+
+/// Column number of column letters, the reverse of [`column_name`]. `None` for anything but letters, or letters too
+/// long to be numbered.
+pub fn column_number(name: &str) -> Option<u32> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    name.chars().try_fold(0u32, |number, ch| {
+        if !ch.is_ascii_alphabetic() {
+            return None;
+        }
+
+        number
+            .checked_mul(26)?
+            .checked_add(u32::from(ch.to_ascii_uppercase() as u8 - b'A') + 1)
+    })
+}
+
+/// Layouts picked for each workbook, so they don't have to be picked again the next time it is opened.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Layouts {
+    #[serde(flatten)]
+    files: HashMap<String, Layout>,
+}
+
+impl Layouts {
+    /// Missing or unreadable files start out empty.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let contents = toml::to_string(self).map_err(std::io::Error::other)?;
+
+        std::fs::write(path, contents)
+    }
+
+    pub fn get(&self, file: &Path) -> Option<&Layout> {
+        self.files.get(&Self::key(file))
+    }
+
+    pub fn insert(&mut self, file: &Path, layout: Layout) {
+        self.files.insert(Self::key(file), layout);
+    }
+
+    fn key(file: &Path) -> String {
+        file.canonicalize()
+            .unwrap_or_else(|_| file.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Reads a `csv` or `tsv` file, with the same columns as the `xlsx` sheet: name, guess and starting score. An optional
+/// fourth column holds the name colour in hex, like `#ed4245`.
+///
+/// A leading header row, starting with `Name`, is skipped.
+pub fn read_delimited<P: AsRef<Path>>(
+    path: P,
+    delimiter: u8,
+) -> Result<Vec<Row>, SpreadsheetError> {
+    let path = path.as_ref();
+
+    let file = std::fs::File::open(path).map_err(|err| SpreadsheetError::Open {
+        path: path.to_path_buf(),
+        reason: err.to_string(),
+    })?;
+
+    parse_delimited(path, file, delimiter)
+}
+
+/// Reads `csv` or `tsv` contents, see [`read_delimited`]. `path` is only used to name the file in errors.
+pub fn parse_delimited<R: std::io::Read>(
+    path: &Path,
+    reader: R,
+    delimiter: u8,
+) -> Result<Vec<Row>, SpreadsheetError> {
+    let mut contents = Vec::new();
 
-        let guess = worksheet.get_value((2, row));
-        let score = worksheet.get_value((3, row)).parse().unwrap_or_default();
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+
+    // Line of the last record read, for errors that don't know their own.
+    let mut last = 0;
+
+    for record in reader.records() {
+        let record = record.map_err(|err| SpreadsheetError::Line {
+            path: path.to_path_buf(),
+            line: err
+                .position()
+                .map_or(last + 1, |position| position.line() as u32),
+            reason: err.to_string(),
+        })?;
+
+        let row = record
+            .position()
+            .map_or(0, |position| position.line() as u32);
+
+        last = row;
+
+        let text = record.get(0).unwrap_or_default().trim();
+
+        if text.is_empty() || (row == 1 && text.eq_ignore_ascii_case("name")) {
+            continue;
+        }
+
+        let guess = record.get(1).unwrap_or_default().to_string();
+        let score = match record.get(2).map(str::trim) {
+            Some(score) if !score.is_empty() => {
+                score.parse().map_err(|_| SpreadsheetError::Line {
+                    path: path.to_path_buf(),
+                    line: row,
+                    reason: format!("starting score `{score}` is not a whole number"),
+                })?
+            }
+            _ => 0,
+        };
+
+        let color = record
+            .get(3)
+            .map(str::trim)
+            .filter(|hex| !hex.is_empty())
+            .and_then(|hex| {
+                if hex.starts_with('#') {
+                    Color32::from_hex(hex).ok()
+                } else {
+                    Color32::from_hex(&format!("#{hex}")).ok()
+                }
+            })
+            .unwrap_or(DEFAULT_COLOR);
 
         contents.push(Row {
             num: row,
             name: Name::new(text.to_string(), color),
             guess,
             starting_score: score,
+            submitted: None,
         });
     }
 
-    contents
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workbook(sheet: &str, cells: &[(&str, &str)]) -> Workbook {
+        let mut spreadsheet = umya_spreadsheet::new_file_empty_worksheet();
+        let worksheet = spreadsheet.new_sheet(sheet).unwrap();
+
+        for (coordinate, value) in cells {
+            worksheet.get_cell_mut(*coordinate).set_value(*value);
+        }
+
+        Workbook {
+            path: PathBuf::from("test.xlsx"),
+            spreadsheet,
+        }
+    }
+
+    #[test]
+    fn should_detect_header() {
+        let workbook = workbook(
+            "Week 3",
+            &[
+                ("A1", "Bingo Week 3"),
+                ("B2", "Score"),
+                ("C2", "Name"),
+                ("D2", "Guesses"),
+                ("B3", "40"),
+                ("C3", "Rolo"),
+                ("D3", "YYNN"),
+            ],
+        );
+
+        let layout = workbook.detect(None);
+
+        assert_eq!(
+            Layout {
+                sheet: String::from("Week 3"),
+                name: 3,
+                guess: 4,
+                score: Some(2),
+                first_row: 3,
+                fallback: default_fallback(),
+            },
+            layout
+        );
+
+        let rows = workbook.read(&layout).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(3, rows[0].num());
+        assert_eq!("Rolo", rows[0].name().text());
+        assert_eq!("YYNN", rows[0].guess());
+        assert_eq!(40, rows[0].starting_score());
+    }
+
+    #[test]
+    fn should_fall_back_to_first_columns() {
+        let workbook = workbook(
+            "Guesses",
+            &[("A1", "Rolo"), ("B1", "YYNN"), ("A3", "Edits")],
+        );
+
+        let layout = workbook.detect(Some("Sheet1"));
+
+        assert_eq!("Guesses", layout.sheet);
+        assert_eq!(
+            (1, 2, Some(3), 1),
+            (layout.name, layout.guess, layout.score, layout.first_row)
+        );
+        assert_eq!(
+            vec!["Rolo", "Edits"],
+            workbook
+                .read(&layout)
+                .unwrap()
+                .iter()
+                .map(|row| row.name().text())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_tint_theme_colors() {
+        let mut workbook = workbook("Sheet1", &[("A1", "Rolo"), ("A2", "Edits"), ("A3", "Bam")]);
+        let worksheet = workbook
+            .spreadsheet
+            .get_sheet_by_name_mut("Sheet1")
+            .unwrap();
+
+        // Accent 1, lighter 40% and darker 25%.
+        for (coordinate, tint) in [
+            ("A1", 0.399_975_585_192_419_2),
+            ("A2", -0.249_977_111_117_893),
+        ] {
+            worksheet
+                .get_style_mut(coordinate)
+                .get_font_mut()
+                .get_color_mut()
+                .set_theme_index(4)
+                .set_tint(tint);
+        }
+
+        worksheet
+            .get_style_mut("A3")
+            .get_font_mut()
+            .get_color_mut()
+            .set_argb("FF000000");
+
+        let mut layout = workbook.detect(None);
+        layout.fallback = String::from("#313338");
+
+        let colors: Vec<Color32> = workbook
+            .read(&layout)
+            .unwrap()
+            .iter()
+            .map(|row| row.name().color())
+            .collect();
+
+        assert_eq!(
+            vec![
+                Color32::from_rgb(0x8f, 0xaa, 0xdc),
+                Color32::from_rgb(0x2f, 0x55, 0x97),
+                Color32::from_rgb(0x31, 0x33, 0x38),
+            ],
+            colors
+        );
+    }
+
+    #[test]
+    fn should_use_rich_text_runs() {
+        let mut workbook = workbook("Sheet1", &[]);
+        let worksheet = workbook
+            .spreadsheet
+            .get_sheet_by_name_mut("Sheet1")
+            .unwrap();
+
+        let mut rich_text = umya_spreadsheet::RichText::default();
+
+        for (text, argb) in [
+            ("@", Some("FF5865F2")),
+            ("Rolo", Some("FFED4245")),
+            (" ", None),
+        ] {
+            let mut run = umya_spreadsheet::TextElement::default();
+            run.set_text(text);
+
+            if let Some(argb) = argb {
+                run.get_run_properties_mut().get_color_mut().set_argb(argb);
+            }
+
+            rich_text.add_rich_text_elements(run);
+        }
+
+        worksheet.get_cell_mut("A1").set_rich_text(rich_text);
+
+        let rows = workbook.read(&workbook.detect(None)).unwrap();
+
+        assert_eq!("@Rolo ", rows[0].name().text());
+        assert_eq!(Color32::from_rgb(0xed, 0x42, 0x45), rows[0].name().color());
+    }
+
+    #[test]
+    fn should_report_cell_errors() {
+        let workbook = workbook("Sheet1", &[("A1", "Rolo"), ("B1", "YYNN"), ("C1", "lots")]);
+
+        let err = workbook.read(&workbook.detect(None)).unwrap_err();
+
+        assert_eq!(
+            "`test.xlsx`, sheet `Sheet1`, cell C1: starting score `lots` is not a whole number",
+            err.to_string()
+        );
+
+        let layout = Layout {
+            sheet: String::from("Week 2"),
+            ..workbook.detect(None)
+        };
+
+        assert!(matches!(
+            workbook.read(&layout),
+            Err(SpreadsheetError::MissingSheet { .. })
+        ));
+        assert!(matches!(
+            Workbook::open("missing.xlsx"),
+            Err(SpreadsheetError::Open { .. })
+        ));
+    }
+
+    #[test]
+    fn should_read_key_row() {
+        let workbook = workbook(
+            "Sheet1",
+            &[
+                ("A1", "Name"),
+                ("B1", "Guess"),
+                ("A2", "Key"),
+                ("B2", "YN?X"),
+                ("A3", "Rolo"),
+                ("B3", "YYNN"),
+            ],
+        );
+
+        let layout = workbook.detect(None);
+
+        assert_eq!(Some(String::from("YN?X")), workbook.key(&layout));
+        assert_eq!(1, workbook.read(&layout).unwrap().len());
+    }
+
+    #[test]
+    fn should_write_key_sheet() {
+        let path = std::env::temp_dir().join(format!("bingo-key-{}.xlsx", std::process::id()));
+
+        let mut workbook = workbook("Sheet1", &[("A1", "Rolo"), ("B1", "YYNN")]);
+        workbook.path.clone_from(&path);
+        umya_spreadsheet::writer::xlsx::write(&workbook.spreadsheet, &path).unwrap();
+
+        let layout = workbook.detect(None);
+        assert_eq!(None, workbook.key(&layout));
+
+        workbook.write_key(&layout, "YNYN").unwrap();
+
+        let reopened = Workbook::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(vec!["Sheet1", "Key"], reopened.sheets());
+        assert_eq!(Some(String::from("YNYN")), reopened.key(&layout));
+        assert_eq!("Sheet1", reopened.detect(None).sheet);
+        assert!(!reopened.is_season());
+    }
+
+    #[test]
+    fn should_keep_changes_made_since_opening() {
+        let path = std::env::temp_dir().join(format!("bingo-changed-{}.xlsx", std::process::id()));
+
+        let mut workbook = workbook("Sheet1", &[("A1", "Rolo"), ("B1", "YYNN")]);
+        workbook.path.clone_from(&path);
+
+        let mut changed = workbook.spreadsheet.clone();
+        let sheet = changed.get_sheet_by_name_mut("Sheet1").unwrap();
+        sheet.get_cell_mut("A2").set_value("Edits");
+        sheet.get_cell_mut("B2").set_value("NNYY");
+        umya_spreadsheet::writer::xlsx::write(&changed, &path).unwrap();
+
+        let layout = workbook.detect(None);
+        workbook.write_key(&layout, "YNYN").unwrap();
+
+        let reopened = Workbook::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for saved in [&workbook, &reopened] {
+            assert_eq!(Some(String::from("YNYN")), saved.key(&layout));
+            assert_eq!(
+                vec!["Rolo", "Edits"],
+                saved
+                    .read(&layout)
+                    .unwrap()
+                    .iter()
+                    .map(|row| row.name.text())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn should_read_weeks() {
+        let mut workbook = workbook(
+            "Week 1",
+            &[
+                ("A1", "Key"),
+                ("B1", "YNYN"),
+                ("A2", "Rolo"),
+                ("B2", "YYNN"),
+            ],
+        );
+
+        let week = workbook.spreadsheet.new_sheet("Week 2").unwrap();
+        week.get_cell_mut("A1").set_value("Name");
+        week.get_cell_mut("C1").set_value("Guess");
+        week.get_cell_mut("A2").set_value("Edits");
+        week.get_cell_mut("C2").set_value("NNNN");
+
+        workbook.spreadsheet.new_sheet(KEY).unwrap();
+
+        assert!(workbook.is_season());
+
+        let weeks = workbook.weeks(&workbook.detect(None)).unwrap();
+
+        let weeks: Vec<(&str, Option<&str>, &str)> = weeks
+            .iter()
+            .map(|week| {
+                (
+                    week.name.as_str(),
+                    week.key.as_deref(),
+                    week.rows[0].guess(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![("Week 1", Some("YNYN"), "YYNN"), ("Week 2", None, "NNNN")],
+            weeks
+        );
+    }
+
+    #[test]
+    fn should_name_columns() {
+        assert_eq!("A", column_name(1));
+        assert_eq!("Z", column_name(26));
+        assert_eq!("AB", column_name(28));
+        assert_eq!(Some(28), column_number("ab"));
+        assert_eq!(None, column_number("A1"));
+        assert_eq!(None, column_number(&"Z".repeat(16)));
+    }
+
+    #[test]
+    fn should_parse_csv() {
+        let csv = "Name,Guess,Score,Color\nRolo,YYNN YNYN,120,#ed4245\n\"Edits, Jr\",NNNN,,\n";
+
+        let rows = parse_delimited(Path::new("guesses.csv"), csv.as_bytes(), b',').unwrap();
+
+        assert_eq!(2, rows.len());
+        assert_eq!(2, rows[0].num());
+        assert_eq!("Rolo", rows[0].name().text());
+        assert_eq!(Color32::from_rgb(0xed, 0x42, 0x45), rows[0].name().color());
+        assert_eq!("YYNN YNYN", rows[0].guess());
+        assert_eq!(120, rows[0].starting_score());
+        assert_eq!("Edits, Jr", rows[1].name().text());
+        assert_eq!(Color32::from_rgb(0xf2, 0xf3, 0xf5), rows[1].name().color());
+    }
+
+    #[test]
+    fn should_parse_tsv() {
+        let tsv = "Rolo\tYYNN\t10\ted4245\nEdits\tNNNN";
+
+        let rows = parse_delimited(Path::new("guesses.tsv"), tsv.as_bytes(), b'\t').unwrap();
+
+        assert_eq!(2, rows.len());
+        assert_eq!(1, rows[0].num());
+        assert_eq!(Color32::from_rgb(0xed, 0x42, 0x45), rows[0].name().color());
+        assert_eq!("NNNN", rows[1].guess());
+    }
+
+    #[test]
+    fn should_reject_invalid_csv_score() {
+        let csv = "Rolo,YYNN,10\nEdits,NNNN,ten\n";
+
+        let err = parse_delimited(Path::new("guesses.csv"), csv.as_bytes(), b',').unwrap_err();
+
+        assert!(matches!(err, SpreadsheetError::Line { line: 2, .. }));
+
+        let csv = b"Rolo,YYNN,10\nEd\xffits,NNNN,0\n";
+
+        let err = parse_delimited(Path::new("guesses.csv"), &csv[..], b',').unwrap_err();
+
+        assert!(matches!(err, SpreadsheetError::Line { line: 2, .. }));
+    }
 }